@@ -0,0 +1,89 @@
+//! Extrai uma task de um relatório salvo e, opcionalmente, a reexecuta com um
+//! comando externo que lê a `Task` em JSON na entrada padrão e escreve o
+//! `TaskResult` em JSON na saída padrão.
+//!
+//! Uso: `kambo-replay <relatorio.json> <task_id> [-- <comando> [args...]]`
+
+use std::{
+    env,
+    error::Error,
+    io::Write,
+    process::{self, Command, Stdio},
+};
+
+use kambo_hive::common::TaskResult;
+use kambo_hive::worker::replay::{ReplayOutcome, load_task_from_report};
+use uuid::Uuid;
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("Erro: {e}");
+        process::exit(2);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (report_path, task_id, command) = match args.as_slice() {
+        [report_path, task_id] => (report_path, task_id, &[][..]),
+        [report_path, task_id, separator, command @ ..]
+            if separator == "--" && !command.is_empty() =>
+        {
+            (report_path, task_id, command)
+        }
+        _ => {
+            return Err(
+                "uso: kambo-replay <relatorio.json> <task_id> [-- <comando> [args...]]".into(),
+            );
+        }
+    };
+
+    let (task, recorded) = load_task_from_report(report_path, Uuid::parse_str(task_id)?)?;
+    if command.is_empty() {
+        println!("{}", serde_json::to_string_pretty(&task)?);
+        return Ok(());
+    }
+
+    let mut child = Command::new(&command[0])
+        .args(&command[1..])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(&serde_json::to_vec(&task)?)?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(format!("Comando terminou com {}", output.status).into());
+    }
+    let mut replayed: TaskResult = serde_json::from_slice(&output.stdout)?;
+    replayed.seed = task.seed;
+
+    let outcome = ReplayOutcome {
+        task,
+        recorded,
+        replayed,
+    };
+    println!(
+        "fitness gravada: {}, reexecutada: {} ({})",
+        outcome.recorded.fitness,
+        outcome.replayed.fitness,
+        if outcome.fitness_matches() {
+            "igual"
+        } else {
+            "DIFERENTE"
+        }
+    );
+    println!(
+        "solução {}",
+        if outcome.solution_matches() {
+            "igual"
+        } else {
+            "DIFERENTE"
+        }
+    );
+    if !outcome.is_reproduced() {
+        process::exit(1);
+    }
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use std::fmt;
+
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const NONCE_LEN: usize = 32;
+
+/// Segredo pré-compartilhado entre o host e os workers do cluster.
+///
+/// O host envia um nonce aleatório na abertura da conexão e o worker responde
+/// com `HMAC-SHA256(segredo, nonce || worker_id)`, de modo que o segredo nunca
+/// trafega pela rede.
+#[derive(Clone)]
+pub struct ClusterSecret {
+    key: Vec<u8>,
+}
+
+impl ClusterSecret {
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        Self { key: key.into() }
+    }
+
+    #[must_use]
+    pub fn generate_nonce() -> Vec<u8> {
+        let mut nonce = vec![0u8; NONCE_LEN];
+        rand::rng().fill_bytes(&mut nonce);
+        nonce
+    }
+
+    #[must_use]
+    pub fn sign(&self, nonce: &[u8], worker_id: Uuid) -> Vec<u8> {
+        self.mac(nonce, worker_id).finalize().into_bytes().to_vec()
+    }
+
+    #[must_use]
+    pub fn verify(&self, nonce: &[u8], worker_id: Uuid, signature: &[u8]) -> bool {
+        self.mac(nonce, worker_id).verify_slice(signature).is_ok()
+    }
+
+    fn mac(&self, nonce: &[u8], worker_id: Uuid) -> HmacSha256 {
+        let mut mac =
+            HmacSha256::new_from_slice(&self.key).expect("HMAC aceita chaves de qualquer tamanho");
+        mac.update(nonce);
+        mac.update(worker_id.as_bytes());
+        mac
+    }
+}
+
+impl fmt::Debug for ClusterSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ClusterSecret(..)")
+    }
+}
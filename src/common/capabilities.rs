@@ -0,0 +1,172 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Recursos e rótulos que um worker anuncia ao se conectar.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkerCapabilities {
+    pub cores: u32,
+    pub memory_mb: u64,
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+}
+
+impl WorkerCapabilities {
+    /// Preenche o número de núcleos da máquina atual. Memória, tags e labels
+    /// devem ser informados pelo usuário.
+    #[must_use]
+    pub fn detect() -> Self {
+        let cores = std::thread::available_parallelism()
+            .map(|cores| cores.get() as u32)
+            .unwrap_or(1);
+        Self {
+            cores,
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    pub fn with_memory_mb(mut self, memory_mb: u64) -> Self {
+        self.memory_mb = memory_mb;
+        self
+    }
+
+    #[must_use]
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.insert(tag.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.labels.insert(key.into(), value.into());
+        self
+    }
+}
+
+/// O que um worker precisa ter para receber uma task.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskRequirements {
+    #[serde(default)]
+    pub min_cores: u32,
+    #[serde(default)]
+    pub min_memory_mb: u64,
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    /// Workers que não podem receber a task.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub excluded_workers: BTreeSet<Uuid>,
+}
+
+impl TaskRequirements {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    #[must_use]
+    pub fn is_satisfied_by(&self, capabilities: &WorkerCapabilities) -> bool {
+        capabilities.cores >= self.min_cores
+            && capabilities.memory_mb >= self.min_memory_mb
+            && self.tags.is_subset(&capabilities.tags)
+            && self
+                .labels
+                .iter()
+                .all(|(key, value)| capabilities.labels.get(key) == Some(value))
+    }
+
+    #[must_use]
+    pub fn with_min_cores(mut self, min_cores: u32) -> Self {
+        self.min_cores = min_cores;
+        self
+    }
+
+    #[must_use]
+    pub fn with_min_memory_mb(mut self, min_memory_mb: u64) -> Self {
+        self.min_memory_mb = min_memory_mb;
+        self
+    }
+
+    #[must_use]
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.insert(tag.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.labels.insert(key.into(), value.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_excluded_worker(mut self, worker_id: Uuid) -> Self {
+        self.excluded_workers.insert(worker_id);
+        self
+    }
+
+    #[must_use]
+    pub fn allows_worker(&self, worker_id: Uuid) -> bool {
+        !self.excluded_workers.contains(&worker_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn worker() -> WorkerCapabilities {
+        WorkerCapabilities {
+            cores: 8,
+            ..WorkerCapabilities::default()
+        }
+        .with_memory_mb(4096)
+        .with_tag("gpu")
+        .with_label("region", "sul")
+    }
+
+    #[test]
+    fn requirements_within_capabilities_are_satisfied() {
+        assert!(TaskRequirements::default().is_satisfied_by(&WorkerCapabilities::default()));
+        let requirements = TaskRequirements::default()
+            .with_min_cores(8)
+            .with_min_memory_mb(4096)
+            .with_tag("gpu")
+            .with_label("region", "sul");
+        assert!(requirements.is_satisfied_by(&worker()));
+    }
+
+    #[test]
+    fn any_missing_capability_rejects_the_worker() {
+        let worker = worker();
+        let base = TaskRequirements::default();
+        assert!(!base.clone().with_min_cores(9).is_satisfied_by(&worker));
+        assert!(
+            !base
+                .clone()
+                .with_min_memory_mb(4097)
+                .is_satisfied_by(&worker)
+        );
+        assert!(!base.clone().with_tag("fpga").is_satisfied_by(&worker));
+        assert!(
+            !base
+                .clone()
+                .with_label("region", "norte")
+                .is_satisfied_by(&worker)
+        );
+        assert!(!base.with_label("rack", "sul").is_satisfied_by(&worker));
+    }
+
+    #[test]
+    fn excluded_workers_are_not_allowed() {
+        let (excluded, other) = (Uuid::new_v4(), Uuid::new_v4());
+        let requirements = TaskRequirements::default().with_excluded_worker(excluded);
+        assert!(!requirements.allows_worker(excluded));
+        assert!(requirements.allows_worker(other));
+    }
+}
@@ -1,7 +1,21 @@
 use uuid::Uuid;
 
-use super::{result::TaskResult, task::Task};
+use super::{progress::ProgressHandle, result::TaskResult, task::Task};
 
-pub trait GARunner: Send + Sync + 'static {
-    fn run(&self, task: Task, worker_id: Uuid) -> TaskResult;
+/// Executa o AG de uma task. `C` e `S` são os tipos da configuração e da
+/// solução; por padrão ficam não tipados (`String` e `Vec<u8>`).
+pub trait GARunner<C = String, S = Vec<u8>>: Send + Sync + 'static {
+    fn run(&self, task: Task<C>, worker_id: Uuid) -> TaskResult<S>;
+
+    /// Chamado pelo worker. Runners que publicam a convergência sobrescrevem
+    /// este método e fazem `run` delegar com `ProgressHandle::disabled`.
+    fn run_with_progress(
+        &self,
+        task: Task<C>,
+        worker_id: Uuid,
+        progress: &ProgressHandle,
+    ) -> TaskResult<S> {
+        let _ = progress;
+        self.run(task, worker_id)
+    }
 }
@@ -1,22 +1,74 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use super::{result::TaskResult, task::Task};
+use super::{
+    capabilities::WorkerCapabilities, progress::ProgressSample, result::TaskResult, task::Task,
+};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum Request {
-    RequestTask { worker_id: Uuid },
-    ReportResult { worker_id: Uuid, result: TaskResult },
-    Heartbeat { worker_id: Uuid },
+    Hello {
+        worker_id: Uuid,
+        #[serde(default)]
+        capabilities: WorkerCapabilities,
+    },
+    Authenticate {
+        worker_id: Uuid,
+        signature: Vec<u8>,
+    },
+    RequestTask {
+        worker_id: Uuid,
+        /// Tempo máximo que o host pode segurar a requisição esperando uma task.
+        #[serde(default)]
+        wait_ms: Option<u64>,
+    },
+    RequestTasks {
+        worker_id: Uuid,
+        max_tasks: u32,
+        #[serde(default)]
+        wait_ms: Option<u64>,
+    },
+    ReportResult {
+        worker_id: Uuid,
+        result: TaskResult,
+    },
+    ReportResults {
+        worker_id: Uuid,
+        results: Vec<TaskResult>,
+    },
+    Heartbeat {
+        worker_id: Uuid,
+    },
+    /// Amostras de convergência de uma task ainda em execução.
+    ReportProgress {
+        worker_id: Uuid,
+        task_id: Uuid,
+        samples: Vec<ProgressSample>,
+    },
+    /// A task não pôde ser executada; o host decide se ela volta para a fila.
+    ReportFailure {
+        worker_id: Uuid,
+        task_id: Uuid,
+        reason: String,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum Response {
     AssignTask {
-        task: Task,
+        task: Box<Task>,
+    },
+    AssignTasks {
+        tasks: Vec<Task>,
     },
     NoTaskAvailable,
     Ack,
+    Challenge {
+        nonce: Vec<u8>,
+    },
+    Unauthorized {
+        reason: String,
+    },
     Command {
         command_type: String,
         payload: String,
@@ -1,9 +1,20 @@
+mod auth;
+mod capabilities;
 mod interfaces;
 mod messages;
+mod objective;
+mod payload;
+mod progress;
 mod result;
 mod task;
 
+pub use auth::ClusterSecret;
+pub use capabilities::{TaskRequirements, WorkerCapabilities};
 pub use interfaces::GARunner;
 pub use messages::{Request, Response};
+pub use objective::Objective;
+pub use payload::{ConfigPayload, JsonPayload, PayloadError, SolutionPayload};
+pub(crate) use progress::progress_channel;
+pub use progress::{ProgressHandle, ProgressSample};
 pub use result::TaskResult;
-pub use task::Task;
+pub use task::{DEFAULT_JOB_ID, ParameterAssignment, Task, derive_task_seed};
@@ -0,0 +1,71 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+/// Direção da otimização. Define o que é a melhor fitness em todo lugar que
+/// compara resultados.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Objective {
+    #[default]
+    Maximize,
+    Minimize,
+}
+
+impl Objective {
+    /// Ordena `a` antes de `b` quando `a` é melhor.
+    #[must_use]
+    pub fn compare(self, a: f64, b: f64) -> Ordering {
+        match self {
+            Self::Maximize => b.total_cmp(&a),
+            Self::Minimize => a.total_cmp(&b),
+        }
+    }
+
+    #[must_use]
+    pub fn is_better(self, candidate: f64, current: f64) -> bool {
+        self.compare(candidate, current) == Ordering::Less
+    }
+
+    /// Valor que qualquer fitness supera; é o resultado de `best` sem amostras.
+    #[must_use]
+    pub const fn worst(self) -> f64 {
+        match self {
+            Self::Maximize => f64::NEG_INFINITY,
+            Self::Minimize => f64::INFINITY,
+        }
+    }
+
+    #[must_use]
+    pub fn best(self, fitness: impl IntoIterator<Item = f64>) -> f64 {
+        fitness.into_iter().fold(self.worst(), |best, value| {
+            if self.is_better(value, best) {
+                value
+            } else {
+                best
+            }
+        })
+    }
+
+    /// Posição de cada valor do melhor para o pior, começando em 1. Empates
+    /// recebem a média das posições.
+    #[must_use]
+    pub fn ranks(self, values: &[f64]) -> Vec<f64> {
+        let mut order: Vec<usize> = (0..values.len()).collect();
+        order.sort_by(|&a, &b| self.compare(values[a], values[b]));
+
+        let mut ranks = vec![0.0; values.len()];
+        let mut start = 0;
+        while start < order.len() {
+            let mut end = start + 1;
+            while end < order.len() && values[order[end]] == values[order[start]] {
+                end += 1;
+            }
+            let rank = (start + end + 1) as f64 / 2.0;
+            for &index in &order[start..end] {
+                ranks[index] = rank;
+            }
+            start = end;
+        }
+        ranks
+    }
+}
@@ -0,0 +1,63 @@
+use std::error::Error;
+
+use serde::{Serialize, de::DeserializeOwned};
+
+pub type PayloadError = Box<dyn Error + Send + Sync>;
+
+/// Tipo que trafega no campo `ag_config` das tasks. `String` passa o texto
+/// adiante sem interpretar.
+pub trait ConfigPayload: Sized {
+    fn encode_config(&self) -> Result<String, PayloadError>;
+    fn decode_config(ag_config: &str) -> Result<Self, PayloadError>;
+}
+
+/// Tipo que trafega no campo `solution_data` dos resultados. `Vec<u8>` passa
+/// os bytes adiante sem interpretar.
+pub trait SolutionPayload: Sized {
+    fn encode_solution(&self) -> Result<Vec<u8>, PayloadError>;
+    fn decode_solution(solution_data: &[u8]) -> Result<Self, PayloadError>;
+}
+
+/// Marca um tipo serde para trafegar como JSON, tanto como configuração quanto
+/// como solução: basta `impl JsonPayload for MinhaConfig {}`.
+pub trait JsonPayload: Serialize + DeserializeOwned {}
+
+impl ConfigPayload for String {
+    fn encode_config(&self) -> Result<String, PayloadError> {
+        Ok(self.clone())
+    }
+
+    fn decode_config(ag_config: &str) -> Result<Self, PayloadError> {
+        Ok(ag_config.to_string())
+    }
+}
+
+impl SolutionPayload for Vec<u8> {
+    fn encode_solution(&self) -> Result<Vec<u8>, PayloadError> {
+        Ok(self.clone())
+    }
+
+    fn decode_solution(solution_data: &[u8]) -> Result<Self, PayloadError> {
+        Ok(solution_data.to_vec())
+    }
+}
+
+impl<T: JsonPayload> ConfigPayload for T {
+    fn encode_config(&self) -> Result<String, PayloadError> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    fn decode_config(ag_config: &str) -> Result<Self, PayloadError> {
+        Ok(serde_json::from_str(ag_config)?)
+    }
+}
+
+impl<T: JsonPayload> SolutionPayload for T {
+    fn encode_solution(&self) -> Result<Vec<u8>, PayloadError> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    fn decode_solution(solution_data: &[u8]) -> Result<Self, PayloadError> {
+        Ok(serde_json::from_slice(solution_data)?)
+    }
+}
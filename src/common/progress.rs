@@ -0,0 +1,126 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::{self, Receiver, Sender, error::TrySendError};
+use uuid::Uuid;
+
+/// Estado do AG em uma geração, enviado ao host enquanto a task executa.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ProgressSample {
+    pub generation: u32,
+    pub best_fitness: f64,
+    pub mean_fitness: f64,
+    /// Tempo desde o início da task.
+    pub elapsed_ms: u64,
+}
+
+/// Canal por onde o runner publica o progresso da task. O worker encaminha as
+/// amostras ao host; fora de um worker o handle apenas as descarta.
+#[derive(Debug, Clone)]
+pub struct ProgressHandle {
+    task_id: Uuid,
+    started_at: Instant,
+    sender: Option<ProgressSender>,
+}
+
+/// Amostra que não coube no canal, mais nova que todas as que estão nele.
+type Overflow = Arc<Mutex<Option<ProgressSample>>>;
+
+fn lock(overflow: &Overflow) -> MutexGuard<'_, Option<ProgressSample>> {
+    overflow.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[derive(Debug, Clone)]
+struct ProgressSender {
+    sender: Sender<ProgressSample>,
+    overflow: Overflow,
+}
+
+/// Lado do worker do canal de progresso.
+pub(crate) struct ProgressReceiver {
+    receiver: Receiver<ProgressSample>,
+    overflow: Overflow,
+}
+
+/// Canal limitado entre o runner e o worker. Com o canal cheio, porque o host
+/// não acompanha o ritmo do AG, as amostras intermediárias são descartadas, mas
+/// a mais recente fica guardada à parte para que o fim da curva não se perca.
+pub(crate) fn progress_channel(
+    task_id: Uuid,
+    capacity: usize,
+) -> (ProgressHandle, ProgressReceiver) {
+    let (sender, receiver) = mpsc::channel(capacity);
+    let overflow = Overflow::default();
+    let handle = ProgressHandle {
+        task_id,
+        started_at: Instant::now(),
+        sender: Some(ProgressSender {
+            sender,
+            overflow: Arc::clone(&overflow),
+        }),
+    };
+    (handle, ProgressReceiver { receiver, overflow })
+}
+
+impl ProgressReceiver {
+    /// Espera a próxima amostra; `None` quando o runner terminou.
+    pub(crate) async fn recv(&mut self) -> Option<ProgressSample> {
+        self.receiver.recv().await
+    }
+
+    /// Amostras já disponíveis, em ordem, incluindo a que não coube no canal.
+    pub(crate) fn drain(&mut self) -> Vec<ProgressSample> {
+        let mut overflow = lock(&self.overflow);
+        let mut samples: Vec<ProgressSample> =
+            std::iter::from_fn(|| self.receiver.try_recv().ok()).collect();
+        samples.extend(overflow.take());
+        samples
+    }
+}
+
+impl ProgressHandle {
+    /// Handle que descarta tudo, para executar um runner fora do worker.
+    #[must_use]
+    pub fn disabled(task_id: Uuid) -> Self {
+        Self {
+            task_id,
+            started_at: Instant::now(),
+            sender: None,
+        }
+    }
+
+    #[must_use]
+    pub fn task_id(&self) -> Uuid {
+        self.task_id
+    }
+
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.sender.is_some()
+    }
+
+    /// Publica uma geração; o tempo decorrido é medido desde a criação do
+    /// handle.
+    pub fn report(&self, generation: u32, best_fitness: f64, mean_fitness: f64) {
+        self.report_sample(ProgressSample {
+            generation,
+            best_fitness,
+            mean_fitness,
+            elapsed_ms: self.started_at.elapsed().as_millis() as u64,
+        });
+    }
+
+    pub fn report_sample(&self, sample: ProgressSample) {
+        let Some(ProgressSender { sender, overflow }) = &self.sender else {
+            return;
+        };
+        let mut overflow = lock(overflow);
+        match sender.try_send(sample) {
+            Ok(()) => *overflow = None,
+            Err(TrySendError::Full(sample)) => *overflow = Some(sample),
+            // O worker já desistiu da task.
+            Err(TrySendError::Closed(_)) => {}
+        }
+    }
+}
@@ -1,13 +1,55 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::payload::{PayloadError, SolutionPayload};
+
+/// Resultado de uma task. `S` é o tipo da solução; na forma não tipada são os
+/// bytes gravados pelo runner.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TaskResult {
+pub struct TaskResult<S = Vec<u8>> {
     pub task_id: Uuid,
     pub graph_id: String,
     pub worker_id: Uuid,
     pub fitness: f64,
-    pub solution_data: Vec<u8>,
+    /// Valores de cada objetivo em problemas multiobjetivo.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub objectives: Vec<f64>,
+    pub solution_data: S,
     pub interations_run: u32,
     pub processing_time_ms: u64,
+    /// Semente usada na execução, copiada de `Task::seed`.
+    #[serde(default)]
+    pub seed: u64,
+}
+
+impl<S> TaskResult<S> {
+    fn map_solution<T>(self, solution_data: T) -> TaskResult<T> {
+        TaskResult {
+            task_id: self.task_id,
+            graph_id: self.graph_id,
+            worker_id: self.worker_id,
+            fitness: self.fitness,
+            objectives: self.objectives,
+            solution_data,
+            interations_run: self.interations_run,
+            processing_time_ms: self.processing_time_ms,
+            seed: self.seed,
+        }
+    }
+}
+
+impl TaskResult {
+    /// Interpreta o `solution_data` como `S`.
+    pub fn into_typed<S: SolutionPayload>(self) -> Result<TaskResult<S>, PayloadError> {
+        let solution_data = S::decode_solution(&self.solution_data)?;
+        Ok(self.map_solution(solution_data))
+    }
+}
+
+impl<S: SolutionPayload> TaskResult<S> {
+    /// Converte para a forma não tipada que o host e a rede usam.
+    pub fn into_untyped(self) -> Result<TaskResult, PayloadError> {
+        let solution_data = self.solution_data.encode_solution()?;
+        Ok(self.map_solution(solution_data))
+    }
 }
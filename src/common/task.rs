@@ -1,21 +1,161 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
+use super::capabilities::TaskRequirements;
+use super::objective::Objective;
+use super::payload::{ConfigPayload, PayloadError};
+
+/// Job usado quando nenhum é informado.
+pub const DEFAULT_JOB_ID: &str = "default";
+
+/// Valores de parâmetros aplicados sobre o `ag_config` base de uma varredura.
+pub type ParameterAssignment = BTreeMap<String, Value>;
+
+fn default_job_id() -> String {
+    DEFAULT_JOB_ID.to_string()
+}
+
+/// Semente de uma execução, derivada da semente mestre do job, do graph e do
+/// número da execução. Usa SHA-256 para ser estável entre plataformas e
+/// versões do compilador.
+#[must_use]
+pub fn derive_task_seed(master_seed: u64, graph_id: &str, run_number: u32) -> u64 {
+    let digest = Sha256::new()
+        .chain_update(master_seed.to_le_bytes())
+        .chain_update((graph_id.len() as u64).to_le_bytes())
+        .chain_update(graph_id.as_bytes())
+        .chain_update(run_number.to_le_bytes())
+        .finalize();
+    let mut seed = [0u8; 8];
+    seed.copy_from_slice(&digest[..8]);
+    u64::from_le_bytes(seed)
+}
+
+/// Unidade de trabalho enviada aos workers. `C` é o tipo da configuração do
+/// AG; na forma não tipada é o texto recebido pelo host.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Task {
+pub struct Task<C = String> {
     pub id: Uuid,
+    #[serde(default = "default_job_id")]
+    pub job_id: String,
     pub graph_id: String,
     pub run_number: u32,
-    pub ag_config: String,
+    pub ag_config: C,
+    /// Semente que o runner deve usar para que a execução seja reproduzível.
+    #[serde(default)]
+    pub seed: u64,
+    /// Direção da otimização, para o runner aplicar em hall da fama, parada
+    /// antecipada e afins.
+    #[serde(default)]
+    pub objective: Objective,
+    /// Direção de cada objetivo em problemas multiobjetivo. Objetivos sem
+    /// direção definida seguem `objective`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub objective_directions: Vec<Objective>,
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default)]
+    pub requirements: TaskRequirements,
+    /// Parâmetros que geraram o `ag_config`, quando a task vem de uma varredura.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub parameters: ParameterAssignment,
+    /// Em reexecuções de verificação, a task original cujo resultado é conferido.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verifies: Option<Uuid>,
 }
 
-impl Task {
-    pub fn new(graph_id: String, run_number: u32, ag_config: String) -> Self {
+impl<C> Task<C> {
+    /// Direções dos `dimensions` objetivos de um resultado multiobjetivo.
+    #[must_use]
+    pub fn directions_for(&self, dimensions: usize) -> Vec<Objective> {
+        (0..dimensions)
+            .map(|dimension| {
+                self.objective_directions
+                    .get(dimension)
+                    .copied()
+                    .unwrap_or(self.objective)
+            })
+            .collect()
+    }
+
+    pub fn new(graph_id: String, run_number: u32, ag_config: C) -> Self {
         Self {
             id: Uuid::new_v4(),
+            job_id: default_job_id(),
             graph_id,
             run_number,
             ag_config,
+            seed: 0,
+            objective: Objective::default(),
+            objective_directions: Vec::new(),
+            priority: 0,
+            requirements: TaskRequirements::default(),
+            parameters: ParameterAssignment::new(),
+            verifies: None,
+        }
+    }
+
+    fn map_config<D>(self, ag_config: D) -> Task<D> {
+        Task {
+            id: self.id,
+            job_id: self.job_id,
+            graph_id: self.graph_id,
+            run_number: self.run_number,
+            ag_config,
+            seed: self.seed,
+            objective: self.objective,
+            objective_directions: self.objective_directions,
+            priority: self.priority,
+            requirements: self.requirements,
+            parameters: self.parameters,
+            verifies: self.verifies,
         }
     }
 }
+
+impl Task {
+    /// Interpreta o `ag_config` como `C`.
+    pub fn into_typed<C: ConfigPayload>(self) -> Result<Task<C>, PayloadError> {
+        let ag_config = C::decode_config(&self.ag_config)?;
+        Ok(self.map_config(ag_config))
+    }
+}
+
+impl<C: ConfigPayload> Task<C> {
+    /// Converte para a forma não tipada que o host e a rede usam.
+    pub fn into_untyped(self) -> Result<Task, PayloadError> {
+        let ag_config = self.ag_config.encode_config()?;
+        Ok(self.map_config(ag_config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn task_seed_is_deterministic() {
+        assert_eq!(derive_task_seed(42, "g", 3), derive_task_seed(42, "g", 3));
+    }
+
+    #[test]
+    fn task_seed_depends_on_every_input() {
+        let seed = derive_task_seed(42, "g", 3);
+        assert_ne!(seed, derive_task_seed(43, "g", 3));
+        assert_ne!(seed, derive_task_seed(42, "h", 3));
+        assert_ne!(seed, derive_task_seed(42, "g", 4));
+        // O tamanho do graph_id entra no hash, então a fronteira entre os
+        // campos não é ambígua.
+        assert_ne!(derive_task_seed(0, "", 1), derive_task_seed(0, "\u{1}", 0));
+    }
+
+    #[test]
+    fn task_seed_is_stable_across_builds() {
+        // Primeiros 8 bytes, little-endian, do SHA-256 da entrada serializada.
+        assert_eq!(derive_task_seed(42, "g", 3), 5_349_514_393_800_253_858);
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::HashSet;
+use std::error::Error;
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::statistics::{mean, sample_variance, student_t_quantile};
+use crate::common::{Task, TaskRequirements};
+
+/// Limites para gerar execuções de um graph até a média da fitness estabilizar.
+#[derive(Debug, Clone, Copy)]
+pub struct AdaptiveRunsConfig {
+    pub min_runs: u32,
+    pub max_runs: u32,
+    /// Largura máxima aceitável do intervalo de confiança da média.
+    pub target_width: f64,
+    pub confidence: f64,
+}
+
+impl AdaptiveRunsConfig {
+    /// `min_runs` é elevado a 2, o mínimo para um intervalo de confiança.
+    pub fn new(min_runs: u32, max_runs: u32, target_width: f64) -> Result<Self, Box<dyn Error>> {
+        let min_runs = min_runs.max(2);
+        if min_runs > max_runs {
+            return Err(format!("min_runs ({min_runs}) maior que max_runs ({max_runs})").into());
+        }
+        Ok(Self {
+            min_runs,
+            max_runs,
+            target_width,
+            confidence: 0.95,
+        })
+    }
+
+    #[must_use]
+    pub const fn with_confidence(mut self, confidence: f64) -> Self {
+        self.confidence = confidence;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ConfidenceInterval {
+    pub samples: usize,
+    pub mean: f64,
+    pub half_width: f64,
+}
+
+impl ConfidenceInterval {
+    /// Intervalo t de Student para a média; exige ao menos duas amostras.
+    #[must_use]
+    pub fn of_mean(samples: &[f64], confidence: f64) -> Option<Self> {
+        if samples.len() < 2 {
+            return None;
+        }
+        let n = samples.len() as f64;
+        let t = student_t_quantile(1.0 - (1.0 - confidence) / 2.0, n - 1.0);
+        Some(Self {
+            samples: samples.len(),
+            mean: mean(samples),
+            half_width: t * (sample_variance(samples) / n).sqrt(),
+        })
+    }
+
+    #[must_use]
+    pub fn width(&self) -> f64 {
+        2.0 * self.half_width
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum AdaptiveStop {
+    Converged,
+    MaxRuns,
+    Aborted,
+}
+
+/// Estado de um graph com número adaptativo de execuções.
+#[derive(Debug, Clone)]
+pub struct AdaptiveGraph {
+    pub job_id: String,
+    pub graph_id: String,
+    pub ag_config: String,
+    pub config: AdaptiveRunsConfig,
+    pub runs_created: u32,
+    pub interval: Option<ConfidenceInterval>,
+    pub stopped: Option<AdaptiveStop>,
+    /// Fitness das execuções adaptativas concluídas; outras execuções do
+    /// mesmo graph não entram no intervalo.
+    pub fitness: Vec<f64>,
+    /// Execuções descartadas depois de esgotar as tentativas.
+    pub failed: u32,
+    pub(crate) task_ids: HashSet<Uuid>,
+    /// Número da primeira execução, depois das que o graph já tinha.
+    pub(crate) first_run: u32,
+    pub(crate) priority: i32,
+    pub(crate) requirements: TaskRequirements,
+}
+
+impl AdaptiveGraph {
+    #[must_use]
+    pub const fn is_finished(&self) -> bool {
+        self.stopped.is_some()
+    }
+
+    /// Cria a próxima execução do graph e passa a acompanhá-la.
+    pub(crate) fn next_task(&mut self) -> Task {
+        let mut task = Task::new(
+            self.graph_id.clone(),
+            self.first_run + self.runs_created,
+            self.ag_config.clone(),
+        );
+        task.job_id.clone_from(&self.job_id);
+        task.priority = self.priority;
+        task.requirements = self.requirements.clone();
+        self.runs_created += 1;
+        self.task_ids.insert(task.id);
+        task
+    }
+
+    /// Registra uma execução descartada e retorna se ela deve ser substituída.
+    /// Sem espaço para substituí-la, o graph encerra quando não restar
+    /// execução em andamento.
+    pub(crate) fn record_failure(&mut self) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+        self.failed += 1;
+        if self.runs_created < self.config.max_runs {
+            return true;
+        }
+        if self.fitness.len() as u32 + self.failed >= self.runs_created {
+            self.stopped = Some(AdaptiveStop::MaxRuns);
+        }
+        false
+    }
+
+    /// Registra a fitness de uma execução adaptativa, atualiza o intervalo e
+    /// retorna quantas execuções novas devem ser criadas.
+    pub(crate) fn update(&mut self, fitness: f64) -> u32 {
+        if self.is_finished() {
+            return 0;
+        }
+        self.fitness.push(fitness);
+        let fitness = self.fitness.as_slice();
+        if fitness.len() < self.config.min_runs as usize {
+            return 0;
+        }
+        let Some(interval) = ConfidenceInterval::of_mean(fitness, self.config.confidence) else {
+            return 0;
+        };
+        self.interval = Some(interval);
+
+        if interval.width() <= self.config.target_width {
+            self.stopped = Some(AdaptiveStop::Converged);
+            return 0;
+        }
+        if self.runs_created >= self.config.max_runs {
+            self.stopped = Some(AdaptiveStop::MaxRuns);
+            return 0;
+        }
+
+        // Estima quantas amostras levariam o intervalo até a largura alvo,
+        // assumindo que o desvio observado se mantém.
+        let n = fitness.len() as f64;
+        let deviation = interval.half_width * n.sqrt();
+        let required = (2.0 * deviation / self.config.target_width).powi(2).ceil();
+        let required = (required as u32).max(fitness.len() as u32 + 1);
+        // Execuções descartadas não produzem amostra, então não contam como
+        // execuções ainda por vir.
+        (required + self.failed)
+            .min(self.config.max_runs)
+            .saturating_sub(self.runs_created)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(config: AdaptiveRunsConfig) -> AdaptiveGraph {
+        let mut graph = AdaptiveGraph {
+            job_id: "job".to_string(),
+            graph_id: "g".to_string(),
+            ag_config: "{}".to_string(),
+            config,
+            runs_created: 0,
+            interval: None,
+            stopped: None,
+            fitness: Vec::new(),
+            failed: 0,
+            task_ids: HashSet::new(),
+            first_run: 0,
+            priority: 0,
+            requirements: TaskRequirements::default(),
+        };
+        for _ in 0..config.min_runs {
+            graph.next_task();
+        }
+        graph
+    }
+
+    #[test]
+    fn config_validates_run_limits() {
+        assert_eq!(AdaptiveRunsConfig::new(0, 10, 1.0).unwrap().min_runs, 2);
+        assert!(AdaptiveRunsConfig::new(5, 3, 1.0).is_err());
+        assert!(AdaptiveRunsConfig::new(1, 1, 1.0).is_err());
+    }
+
+    #[test]
+    fn confidence_interval_of_known_samples() {
+        assert!(ConfidenceInterval::of_mean(&[1.0], 0.95).is_none());
+
+        // Média 3, desvio sqrt(2.5) e t(0.975, 4) = 2.776.
+        let interval = ConfidenceInterval::of_mean(&[1.0, 2.0, 3.0, 4.0, 5.0], 0.95).unwrap();
+        assert_eq!(interval.samples, 5);
+        assert!((interval.mean - 3.0).abs() < 1e-12);
+        let expected = 2.776 * (2.5_f64 / 5.0).sqrt();
+        assert!((interval.half_width - expected).abs() < 2e-2);
+    }
+
+    #[test]
+    fn stable_fitness_converges_at_min_runs() {
+        let mut graph = graph(AdaptiveRunsConfig::new(3, 20, 0.5).unwrap());
+        assert_eq!(graph.update(10.0), 0);
+        assert_eq!(graph.update(10.0), 0);
+        assert_eq!(graph.update(10.0), 0);
+        assert_eq!(graph.stopped, Some(AdaptiveStop::Converged));
+        assert_eq!(graph.update(10.0), 0);
+    }
+
+    #[test]
+    fn noisy_fitness_asks_for_more_runs_up_to_max() {
+        let mut graph = graph(AdaptiveRunsConfig::new(3, 8, 0.1).unwrap());
+        graph.update(0.0);
+        graph.update(100.0);
+        let new_runs = graph.update(50.0);
+        assert!(new_runs > 0);
+        assert!(graph.runs_created + new_runs <= 8);
+        assert!(graph.stopped.is_none());
+    }
+
+    #[test]
+    fn stops_at_max_runs() {
+        let mut graph = graph(AdaptiveRunsConfig::new(2, 2, 0.1).unwrap());
+        graph.update(0.0);
+        assert_eq!(graph.update(100.0), 0);
+        assert_eq!(graph.stopped, Some(AdaptiveStop::MaxRuns));
+    }
+
+    #[test]
+    fn requested_runs_never_exceed_max_runs() {
+        let mut graph = graph(AdaptiveRunsConfig::new(2, 3, 0.1).unwrap());
+        graph.update(0.0);
+        assert_eq!(graph.update(100.0), 1);
+        graph.next_task();
+        assert_eq!(graph.update(50.0), 0);
+        assert_eq!(graph.stopped, Some(AdaptiveStop::MaxRuns));
+    }
+
+    #[test]
+    fn failed_runs_are_replaced_until_max_runs() {
+        let mut graph = graph(AdaptiveRunsConfig::new(2, 3, 0.1).unwrap());
+        assert!(graph.record_failure());
+        graph.next_task();
+        assert!(!graph.record_failure());
+        assert!(graph.stopped.is_none());
+        assert!(!graph.record_failure());
+        assert_eq!(graph.stopped, Some(AdaptiveStop::MaxRuns));
+    }
+
+    #[test]
+    fn replaced_failures_do_not_count_as_pending_runs() {
+        let mut graph = graph(AdaptiveRunsConfig::new(3, 50, 1.0).unwrap());
+        assert!(graph.record_failure());
+        graph.next_task();
+        assert_eq!(graph.update(0.0), 0);
+        assert_eq!(graph.update(0.202), 0);
+        // As três execuções restantes terminaram sem convergir: sem pedir
+        // mais, o graph ficaria sem execução em andamento e nunca encerraria.
+        assert!(graph.update(0.404) > 0);
+        assert!(graph.stopped.is_none());
+    }
+}
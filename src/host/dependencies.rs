@@ -0,0 +1,61 @@
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+use crate::common::{Task, TaskResult};
+
+/// Algo que precisa terminar antes de um estágio seguinte ser liberado.
+/// Sempre relativo ao job do estágio.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Dependency {
+    Task(Uuid),
+    /// Todas as execuções do graph adicionadas até o registro do estágio, e as
+    /// que fontes, graphs adaptativos ou corridas adicionarem depois. Tasks
+    /// liberadas por outros estágios após o registro não contam.
+    AllRunsOfGraph(String),
+}
+
+impl Dependency {
+    #[must_use]
+    pub fn matches(&self, task: &Task) -> bool {
+        match self {
+            Self::Task(task_id) => task.id == *task_id,
+            Self::AllRunsOfGraph(graph_id) => task.graph_id == *graph_id,
+        }
+    }
+}
+
+/// Gera as tasks de um estágio a partir dos resultados das dependências.
+pub type FollowUpGenerator = Box<dyn FnMut(&[TaskResult]) -> Vec<Task> + Send>;
+
+pub(crate) enum Downstream {
+    Tasks(Vec<Task>),
+    Generator(FollowUpGenerator),
+}
+
+pub(crate) struct Stage {
+    pub id: Uuid,
+    pub job_id: String,
+    pub dependencies: Vec<Dependency>,
+    pub downstream: Downstream,
+    /// Tasks das quais o estágio depende, resolvidas a partir de
+    /// `dependencies`.
+    pub upstream: HashSet<Uuid>,
+    /// Resultados das dependências recebidos desde o registro do estágio.
+    pub upstream_results: Vec<TaskResult>,
+}
+
+impl Stage {
+    pub fn depends_on(&self, task: &Task) -> bool {
+        task.job_id == self.job_id && self.upstream.contains(&task.id)
+    }
+
+    /// Se `task`, recém-enfileirada, também é uma execução esperada pelo
+    /// estágio.
+    pub fn waits_for_graph(&self, task: &Task) -> bool {
+        task.job_id == self.job_id
+            && self.dependencies.iter().any(|dependency| {
+                matches!(dependency, Dependency::AllRunsOfGraph(_)) && dependency.matches(task)
+            })
+    }
+}
@@ -1,4 +1,13 @@
+pub mod adaptive;
+pub mod dependencies;
+pub mod pareto;
 pub mod periodic_saver;
+pub mod racing;
 pub mod result_aggregator;
+pub mod scheduling;
 pub mod server;
+pub mod statistics;
+pub mod sweep;
 pub mod task_manager;
+pub mod task_source;
+pub mod verification;
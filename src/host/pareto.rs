@@ -0,0 +1,289 @@
+use log::debug;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::common::Objective;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParetoPoint {
+    pub task_id: Uuid,
+    pub worker_id: Uuid,
+    pub objectives: Vec<f64>,
+}
+
+/// Conjunto não dominado dos resultados de um graph, mantido conforme os
+/// resultados chegam.
+#[derive(Debug, Clone)]
+pub struct ParetoFront {
+    directions: Vec<Objective>,
+    points: Vec<ParetoPoint>,
+}
+
+impl ParetoFront {
+    #[must_use]
+    pub const fn new(directions: Vec<Objective>) -> Self {
+        Self {
+            directions,
+            points: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn directions(&self) -> &[Objective] {
+        &self.directions
+    }
+
+    #[must_use]
+    pub fn points(&self) -> &[ParetoPoint] {
+        &self.points
+    }
+
+    /// `a` domina `b` se não é pior em nenhum objetivo e é melhor em algum.
+    #[must_use]
+    pub fn dominates(&self, a: &[f64], b: &[f64]) -> bool {
+        dominates_with(&self.directions, a, b)
+    }
+
+    /// Insere o ponto se ele não for dominado nem repetido, descartando os que
+    /// ele domina. Retorna se o ponto entrou na frente.
+    pub fn insert(&mut self, point: ParetoPoint) -> bool {
+        if point.objectives.len() != self.directions.len() {
+            return false;
+        }
+        if self.points.iter().any(|existing| {
+            existing.objectives == point.objectives
+                || self.dominates(&existing.objectives, &point.objectives)
+        }) {
+            return false;
+        }
+
+        let directions = &self.directions;
+        self.points.retain(|existing| {
+            !dominates_with(directions, &point.objectives, &existing.objectives)
+        });
+        self.points.push(point);
+        true
+    }
+
+    /// Melhor valor de cada objetivo na frente.
+    #[must_use]
+    pub fn ideal(&self) -> Vec<f64> {
+        self.directions
+            .iter()
+            .enumerate()
+            .map(|(dimension, direction)| {
+                direction.best(self.points.iter().map(|point| point.objectives[dimension]))
+            })
+            .collect()
+    }
+
+    /// Pior valor de cada objetivo na frente.
+    #[must_use]
+    pub fn nadir(&self) -> Vec<f64> {
+        self.directions
+            .iter()
+            .enumerate()
+            .map(|(dimension, direction)| {
+                self.points
+                    .iter()
+                    .map(|point| point.objectives[dimension])
+                    .fold(-direction.worst(), |nadir, value| {
+                        if direction.is_better(nadir, value) {
+                            value
+                        } else {
+                            nadir
+                        }
+                    })
+            })
+            .collect()
+    }
+
+    /// Volume dominado pela frente e limitado pelo ponto de referência, que
+    /// deve ser pior que a frente em todos os objetivos. Pontos que não
+    /// dominam a referência não contribuem. Retorna `None` se a referência não
+    /// tiver um valor por objetivo.
+    #[must_use]
+    pub fn hypervolume(&self, reference_point: &[f64]) -> Option<f64> {
+        if reference_point.len() != self.directions.len() {
+            return None;
+        }
+
+        // Converte tudo para minimização.
+        let orient = |values: &[f64]| -> Vec<f64> {
+            values
+                .iter()
+                .zip(&self.directions)
+                .map(|(&value, direction)| match direction {
+                    Objective::Minimize => value,
+                    Objective::Maximize => -value,
+                })
+                .collect()
+        };
+        let reference = orient(reference_point);
+        let points: Vec<Vec<f64>> = self
+            .points
+            .iter()
+            .map(|point| orient(&point.objectives))
+            .filter(|point| point.iter().zip(&reference).all(|(p, r)| p < r))
+            .collect();
+        if points.len() < self.points.len() {
+            debug!(
+                "{} de {} pontos da frente não dominam a referência {reference_point:?} e ficaram fora do hipervolume",
+                self.points.len() - points.len(),
+                self.points.len()
+            );
+        }
+        Some(hypervolume_min(points, &reference))
+    }
+}
+
+fn dominates_with(directions: &[Objective], a: &[f64], b: &[f64]) -> bool {
+    let mut strictly_better = false;
+    for ((&a, &b), direction) in a.iter().zip(b).zip(directions) {
+        if direction.is_better(b, a) {
+            return false;
+        }
+        strictly_better |= direction.is_better(a, b);
+    }
+    strictly_better
+}
+
+/// Hipervolume por fatiamento no último objetivo (HSO), com todos os
+/// objetivos minimizados e pontos já dentro da referência.
+fn hypervolume_min(mut points: Vec<Vec<f64>>, reference: &[f64]) -> f64 {
+    let dimensions = reference.len();
+    if points.is_empty() || dimensions == 0 {
+        return 0.0;
+    }
+    if dimensions == 1 {
+        let best = points
+            .iter()
+            .map(|point| point[0])
+            .fold(f64::INFINITY, f64::min);
+        return reference[0] - best;
+    }
+
+    let last = dimensions - 1;
+    points.sort_by(|a, b| a[last].total_cmp(&b[last]));
+
+    let mut volume = 0.0;
+    for i in 0..points.len() {
+        let upper = points.get(i + 1).map_or(reference[last], |next| next[last]);
+        let depth = upper - points[i][last];
+        if depth <= 0.0 {
+            continue;
+        }
+        let slice: Vec<Vec<f64>> = points[..=i]
+            .iter()
+            .map(|point| point[..last].to_vec())
+            .collect();
+        volume += depth * hypervolume_min(slice, &reference[..last]);
+    }
+    volume
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn front(directions: Vec<Objective>, points: &[&[f64]]) -> ParetoFront {
+        let mut front = ParetoFront::new(directions);
+        for objectives in points {
+            front.insert(ParetoPoint {
+                task_id: Uuid::new_v4(),
+                worker_id: Uuid::nil(),
+                objectives: objectives.to_vec(),
+            });
+        }
+        front
+    }
+
+    fn assert_close(actual: Option<f64>, expected: f64) {
+        let actual = actual.expect("hipervolume definido");
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "esperado {expected}, obtido {actual}"
+        );
+    }
+
+    #[test]
+    fn insert_keeps_only_non_dominated_points() {
+        let front = front(
+            vec![Objective::Minimize; 2],
+            &[
+                &[2.0, 2.0],
+                &[1.0, 3.0],
+                &[3.0, 3.0],
+                &[2.0, 2.0],
+                &[1.0, 1.5],
+            ],
+        );
+        let mut points: Vec<_> = front
+            .points()
+            .iter()
+            .map(|p| p.objectives.clone())
+            .collect();
+        points.sort_by(|a, b| a[0].total_cmp(&b[0]));
+        assert_eq!(points, vec![vec![1.0, 1.5]]);
+    }
+
+    #[test]
+    fn hypervolume_2d_staircase() {
+        let front = front(
+            vec![Objective::Minimize; 2],
+            &[&[1.0, 3.0], &[2.0, 2.0], &[3.0, 1.0]],
+        );
+        assert_close(front.hypervolume(&[4.0, 4.0]), 6.0);
+    }
+
+    #[test]
+    fn hypervolume_2d_maximize() {
+        let front = front(vec![Objective::Maximize; 2], &[&[3.0, 1.0], &[1.0, 3.0]]);
+        assert_close(front.hypervolume(&[0.0, 0.0]), 5.0);
+    }
+
+    #[test]
+    fn hypervolume_3d_single_point_is_a_box() {
+        let front = front(vec![Objective::Minimize; 3], &[&[1.0, 1.0, 1.0]]);
+        assert_close(front.hypervolume(&[2.0, 3.0, 4.0]), 6.0);
+    }
+
+    #[test]
+    fn hypervolume_3d_overlapping_boxes() {
+        let front = front(
+            vec![Objective::Minimize; 3],
+            &[&[1.0, 2.0, 2.0], &[2.0, 1.0, 1.0]],
+        );
+        // 4 + 2 menos a interseção de volume 1.
+        assert_close(front.hypervolume(&[3.0, 3.0, 3.0]), 5.0);
+    }
+
+    #[test]
+    fn hypervolume_mixed_directions() {
+        let front = front(
+            vec![Objective::Minimize, Objective::Maximize],
+            &[&[1.0, 2.0], &[2.0, 3.0]],
+        );
+        // Caixas [1,3]x[0,2] e [2,3]x[0,3]: 4 + 3 - 2.
+        assert_close(front.hypervolume(&[3.0, 0.0]), 5.0);
+    }
+
+    #[test]
+    fn hypervolume_ignores_points_outside_reference() {
+        let front = front(vec![Objective::Minimize; 2], &[&[1.0, 3.0], &[5.0, 0.0]]);
+        assert_close(front.hypervolume(&[4.0, 4.0]), 3.0);
+    }
+
+    #[test]
+    fn hypervolume_rejects_reference_with_wrong_length() {
+        let front = front(vec![Objective::Minimize; 2], &[&[1.0, 1.0]]);
+        assert_eq!(front.hypervolume(&[2.0]), None);
+        assert_eq!(front.hypervolume(&[2.0, 2.0, 2.0]), None);
+    }
+
+    #[test]
+    fn empty_front_has_zero_hypervolume() {
+        let front = front(vec![Objective::Minimize; 2], &[]);
+        assert_close(front.hypervolume(&[1.0, 1.0]), 0.0);
+    }
+}
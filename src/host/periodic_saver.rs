@@ -14,9 +14,12 @@ struct SaverTaskResult {
     pub task_id: Uuid,
     pub worker_id: Uuid,
     pub fitness: f64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub objectives: Vec<f64>,
     pub solution_data: Vec<u8>,
     pub interations_run: u32,
     pub processing_time_ms: u64,
+    pub seed: u64,
 }
 
 #[derive(Serialize)]
@@ -25,8 +28,15 @@ struct SaverResults {
     results: Vec<SaverTaskResult>,
 }
 
-pub fn start(aggregator: Arc<Mutex<ResultAggregator>>, file_path: String, interval_secs: u64) {
-    info!("Salvamento periódico ativado. Arquivo: '{file_path}', Intervalo: {interval_secs}s.");
+pub fn start(
+    aggregator: Arc<Mutex<ResultAggregator>>,
+    job_id: String,
+    file_path: String,
+    interval_secs: u64,
+) {
+    info!(
+        "Salvamento periódico do job {job_id} ativado. Arquivo: '{file_path}', Intervalo: {interval_secs}s."
+    );
 
     tokio::spawn(async move {
         let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
@@ -35,19 +45,22 @@ pub fn start(aggregator: Arc<Mutex<ResultAggregator>>, file_path: String, interv
             interval.tick().await;
             let results_guard = aggregator.lock().await;
 
-            if results_guard.get_results_collected() == 0 {
-                info!("Nenhum resultado para salvar, pulando ciclo de salvamento.");
+            let Some(all_results) = results_guard
+                .get_all_results(&job_id)
+                .filter(|_| results_guard.get_results_collected(&job_id) > 0)
+            else {
+                info!("Nenhum resultado do job {job_id} para salvar, pulando ciclo de salvamento.");
                 continue;
-            }
+            };
 
             info!(
-                "Preparando {} resultados para salvar em '{}'...",
-                results_guard.get_results_collected(),
+                "Preparando {} resultados do job {} para salvar em '{}'...",
+                results_guard.get_results_collected(&job_id),
+                job_id,
                 file_path
             );
 
-            let formatted_results: Vec<SaverResults> = results_guard
-                .get_all_results()
+            let formatted_results: Vec<SaverResults> = all_results
                 .iter()
                 .map(|(graph_name, task_results)| SaverResults {
                     name: graph_name.clone(),
@@ -57,9 +70,11 @@ pub fn start(aggregator: Arc<Mutex<ResultAggregator>>, file_path: String, interv
                             task_id: tr.task_id,
                             worker_id: tr.worker_id,
                             fitness: tr.fitness,
+                            objectives: tr.objectives.clone(),
                             solution_data: tr.solution_data.clone(),
                             interations_run: tr.interations_run,
                             processing_time_ms: tr.processing_time_ms,
+                            seed: tr.seed,
                         })
                         .collect(),
                 })
@@ -0,0 +1,541 @@
+use std::collections::HashMap;
+
+use log::info;
+use uuid::Uuid;
+
+use super::statistics::{chi_squared_quantile, mean, student_t_quantile};
+use super::sweep::ParameterSweep;
+use crate::common::{
+    ConfigPayload, Objective, ParameterAssignment, PayloadError, Task, TaskRequirements,
+};
+
+/// Configuração candidata numa corrida.
+#[derive(Debug, Clone)]
+pub struct RaceCandidate {
+    pub ag_config: String,
+    pub parameters: ParameterAssignment,
+    /// Rodada em que o candidato foi eliminado, se foi.
+    pub eliminated_in_round: Option<u32>,
+    /// Fitness em cada rodada concluída enquanto o candidato estava vivo.
+    pub fitness: Vec<f64>,
+}
+
+impl RaceCandidate {
+    fn new(ag_config: String, parameters: ParameterAssignment) -> Self {
+        Self {
+            ag_config,
+            parameters,
+            eliminated_in_round: None,
+            fitness: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn is_alive(&self) -> bool {
+        self.eliminated_in_round.is_none()
+    }
+
+    #[must_use]
+    pub fn mean_fitness(&self) -> f64 {
+        mean(&self.fitness)
+    }
+}
+
+/// Parâmetros de uma corrida no estilo F-Race: a cada rodada todos os
+/// candidatos vivos rodam na mesma instância (graph e número da execução), e a
+/// partir de `first_test` rodadas o teste de Friedman elimina os piores.
+#[derive(Debug, Clone)]
+pub struct RaceConfig {
+    candidates: Vec<RaceCandidate>,
+    graphs: Vec<String>,
+    budget: usize,
+    alpha: f64,
+    first_test: u32,
+}
+
+impl RaceConfig {
+    pub fn new<I, S>(graphs: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            candidates: Vec::new(),
+            graphs: graphs.into_iter().map(Into::into).collect(),
+            budget: 1000,
+            alpha: 0.05,
+            first_test: 5,
+        }
+    }
+
+    #[must_use]
+    pub fn with_candidate(mut self, ag_config: impl Into<String>) -> Self {
+        self.candidates.push(RaceCandidate::new(
+            ag_config.into(),
+            ParameterAssignment::new(),
+        ));
+        self
+    }
+
+    /// Igual a `with_candidate`, com a configuração já tipada.
+    pub fn with_typed_candidate<C: ConfigPayload>(
+        self,
+        ag_config: &C,
+    ) -> Result<Self, PayloadError> {
+        Ok(self.with_candidate(ag_config.encode_config()?))
+    }
+
+    /// Adiciona como candidatas todas as configurações da varredura.
+    #[must_use]
+    pub fn with_sweep_candidates(mut self, sweep: &ParameterSweep) -> Self {
+        for assignment in sweep.assignments() {
+            let ag_config = sweep.config_for(&assignment);
+            self.candidates
+                .push(RaceCandidate::new(ag_config, assignment));
+        }
+        self
+    }
+
+    /// Número máximo de execuções somando todas as rodadas.
+    #[must_use]
+    pub const fn with_budget(mut self, budget: usize) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_alpha(mut self, alpha: f64) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    #[must_use]
+    pub fn graphs(&self) -> &[String] {
+        &self.graphs
+    }
+
+    /// Rodadas concluídas antes do primeiro teste estatístico.
+    #[must_use]
+    pub const fn with_first_test(mut self, first_test: u32) -> Self {
+        self.first_test = first_test;
+        self
+    }
+}
+
+#[derive(Debug)]
+struct Round {
+    graph_id: String,
+    objective: Objective,
+    tasks: HashMap<Uuid, usize>,
+    fitness: HashMap<usize, f64>,
+}
+
+#[derive(Debug)]
+pub struct Race {
+    id: Uuid,
+    job_id: String,
+    candidates: Vec<RaceCandidate>,
+    graphs: Vec<String>,
+    budget: usize,
+    alpha: f64,
+    first_test: u32,
+    priority: i32,
+    requirements: TaskRequirements,
+    rounds_started: u32,
+    rounds_completed: u32,
+    /// Direção da otimização de cada rodada concluída, usada nos ranks.
+    round_objectives: Vec<Objective>,
+    evaluations: usize,
+    round: Option<Round>,
+    finished: bool,
+}
+
+impl Race {
+    pub(crate) fn new(
+        job_id: &str,
+        config: RaceConfig,
+        priority: i32,
+        requirements: TaskRequirements,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            job_id: job_id.to_string(),
+            candidates: config.candidates,
+            graphs: config.graphs,
+            budget: config.budget,
+            alpha: config.alpha,
+            first_test: config.first_test.max(2),
+            priority,
+            requirements,
+            rounds_started: 0,
+            rounds_completed: 0,
+            round_objectives: Vec::new(),
+            evaluations: 0,
+            round: None,
+            finished: false,
+        }
+    }
+
+    #[must_use]
+    pub const fn id(&self) -> Uuid {
+        self.id
+    }
+
+    #[must_use]
+    pub fn job_id(&self) -> &str {
+        &self.job_id
+    }
+
+    #[must_use]
+    pub fn candidates(&self) -> &[RaceCandidate] {
+        &self.candidates
+    }
+
+    pub fn survivors(&self) -> impl Iterator<Item = &RaceCandidate> {
+        self.candidates
+            .iter()
+            .filter(|candidate| candidate.is_alive())
+    }
+
+    /// Sobrevivente com a menor soma de ranks entre os vivos.
+    #[must_use]
+    pub fn best(&self) -> Option<&RaceCandidate> {
+        let alive = self.alive();
+        let (rank_sums, _) = self.rank_sums(&alive);
+        alive
+            .iter()
+            .zip(rank_sums)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(&index, _)| &self.candidates[index])
+    }
+
+    fn alive(&self) -> Vec<usize> {
+        (0..self.candidates.len())
+            .filter(|&index| self.candidates[index].is_alive())
+            .collect()
+    }
+
+    /// Soma dos ranks de cada candidato em `alive` e soma dos quadrados de
+    /// todos os ranks, sobre as rodadas concluídas.
+    fn rank_sums(&self, alive: &[usize]) -> (Vec<f64>, f64) {
+        let mut rank_sums = vec![0.0; alive.len()];
+        let mut rank_squares = 0.0;
+        for (block, objective) in self.round_objectives.iter().enumerate() {
+            let values: Vec<f64> = alive
+                .iter()
+                .map(|&index| self.candidates[index].fitness[block])
+                .collect();
+            for (position, rank) in objective.ranks(&values).into_iter().enumerate() {
+                rank_sums[position] += rank;
+                rank_squares += rank * rank;
+            }
+        }
+        (rank_sums, rank_squares)
+    }
+
+    #[must_use]
+    pub const fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    #[must_use]
+    pub const fn rounds_completed(&self) -> u32 {
+        self.rounds_completed
+    }
+
+    #[must_use]
+    pub const fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+
+    pub(crate) const fn needs_round(&self) -> bool {
+        !self.finished && self.round.is_none()
+    }
+
+    /// Se a corrida ainda pode criar rodadas no graph.
+    pub(crate) fn runs_graph(&self, graph_id: &str) -> bool {
+        !self.finished && self.graphs.iter().any(|graph| graph == graph_id)
+    }
+
+    pub(crate) fn owns(&self, task_id: Uuid) -> bool {
+        self.round
+            .as_ref()
+            .is_some_and(|round| round.tasks.contains_key(&task_id))
+    }
+
+    /// Cria as tasks da próxima rodada, ou encerra a corrida se não houver
+    /// mais orçamento ou candidatos para comparar.
+    pub(crate) fn next_round_tasks(&mut self) -> Vec<Task> {
+        let alive = self.alive();
+        if alive.len() <= 1
+            || self.graphs.is_empty()
+            || self.evaluations + alive.len() > self.budget
+        {
+            self.stop();
+            return Vec::new();
+        }
+
+        let graph_id = self.graphs[self.rounds_started as usize % self.graphs.len()].clone();
+        let run_number = self.rounds_started / self.graphs.len() as u32;
+        self.rounds_started += 1;
+        self.evaluations += alive.len();
+
+        let mut round = Round {
+            graph_id: graph_id.clone(),
+            objective: Objective::default(),
+            tasks: HashMap::with_capacity(alive.len()),
+            fitness: HashMap::with_capacity(alive.len()),
+        };
+        let tasks = alive
+            .into_iter()
+            .map(|index| {
+                let candidate = &self.candidates[index];
+                let mut task = Task::new(graph_id.clone(), run_number, candidate.ag_config.clone());
+                task.job_id.clone_from(&self.job_id);
+                task.priority = self.priority;
+                task.requirements = self.requirements.clone();
+                task.parameters = candidate.parameters.clone();
+                round.tasks.insert(task.id, index);
+                task
+            })
+            .collect();
+        self.round = Some(round);
+        tasks
+    }
+
+    /// Registra a fitness de uma task da rodada atual e, quando todas
+    /// chegarem, fecha a rodada e aplica o teste.
+    pub(crate) fn record(&mut self, task_id: Uuid, fitness: f64, objective: Objective) {
+        let Some(round) = &mut self.round else {
+            return;
+        };
+        let Some(&index) = round.tasks.get(&task_id) else {
+            return;
+        };
+        round.objective = objective;
+        round.fitness.insert(index, fitness);
+        self.close_round_if_complete();
+    }
+
+    /// Elimina o candidato de uma task descartada após falhar, já que ele não
+    /// terá fitness na rodada atual.
+    pub(crate) fn discard(&mut self, task_id: Uuid) {
+        let Some(round) = &mut self.round else {
+            return;
+        };
+        let Some(index) = round.tasks.remove(&task_id) else {
+            return;
+        };
+        info!(
+            "Candidato {index} da corrida {} eliminado após falhar na rodada {}",
+            self.id, self.rounds_started
+        );
+        self.candidates[index].eliminated_in_round = Some(self.rounds_started);
+        if round.tasks.is_empty() {
+            self.round = None;
+            return;
+        }
+        self.close_round_if_complete();
+    }
+
+    fn close_round_if_complete(&mut self) {
+        if self
+            .round
+            .as_ref()
+            .is_none_or(|round| round.fitness.len() < round.tasks.len())
+        {
+            return;
+        }
+
+        if let Some(round) = self.round.take() {
+            for (index, fitness) in round.fitness {
+                self.candidates[index].fitness.push(fitness);
+            }
+            self.round_objectives.push(round.objective);
+        }
+        self.rounds_completed += 1;
+        if self.rounds_completed >= self.first_test {
+            self.eliminate();
+        }
+    }
+
+    /// Remove um graph das instâncias; a rodada em andamento nele é descartada.
+    pub(crate) fn drop_graph(&mut self, graph_id: &str) {
+        self.graphs.retain(|graph| graph != graph_id);
+        if self
+            .round
+            .as_ref()
+            .is_some_and(|round| round.graph_id == graph_id)
+        {
+            self.round = None;
+        }
+    }
+
+    pub(crate) fn stop(&mut self) {
+        if !self.finished {
+            info!(
+                "Corrida {} encerrada após {} rodadas e {} execuções",
+                self.id, self.rounds_completed, self.evaluations
+            );
+        }
+        self.finished = true;
+        self.round = None;
+    }
+
+    /// Teste de Friedman seguido das comparações de Conover contra o melhor.
+    fn eliminate(&mut self) {
+        let alive = self.alive();
+        let k = alive.len();
+        let blocks = self.round_objectives.len();
+        if k < 2 || blocks < 2 {
+            return;
+        }
+
+        let (rank_sums, rank_squares) = self.rank_sums(&alive);
+
+        let (k_f, b_f) = (k as f64, blocks as f64);
+        let correction = b_f * k_f * (k_f + 1.0).powi(2) / 4.0;
+        if rank_squares - correction <= f64::EPSILON {
+            return;
+        }
+        let expected = b_f * (k_f + 1.0) / 2.0;
+        let statistic = (k_f - 1.0)
+            * rank_sums
+                .iter()
+                .map(|sum| (sum - expected).powi(2))
+                .sum::<f64>()
+            / (rank_squares - correction);
+        if statistic <= chi_squared_quantile(1.0 - self.alpha, k_f - 1.0) {
+            return;
+        }
+
+        let df = (b_f - 1.0) * (k_f - 1.0);
+        let sum_of_squared_sums: f64 = rank_sums.iter().map(|sum| sum * sum).sum();
+        let critical = student_t_quantile(1.0 - self.alpha / 2.0, df)
+            * (2.0 * (b_f * rank_squares - sum_of_squared_sums) / df).sqrt();
+        let best = rank_sums.iter().copied().fold(f64::INFINITY, f64::min);
+
+        let round = self.rounds_completed;
+        for (position, &index) in alive.iter().enumerate() {
+            if rank_sums[position] - best > critical {
+                self.candidates[index].eliminated_in_round = Some(round);
+            }
+        }
+        info!(
+            "Corrida {}: rodada {round}, {} de {k} candidatos sobreviveram",
+            self.id,
+            self.survivors().count()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn race(config: RaceConfig) -> Race {
+        Race::new("job", config, 0, TaskRequirements::default())
+    }
+
+    fn config() -> RaceConfig {
+        RaceConfig::new(["g1", "g2"])
+            .with_candidate("good")
+            .with_candidate("mid")
+            .with_candidate("bad")
+    }
+
+    fn fitness_of(ag_config: &str) -> f64 {
+        match ag_config {
+            "good" => 3.0,
+            "mid" => 2.0,
+            _ => 1.0,
+        }
+    }
+
+    /// Executa uma rodada completa e retorna quantas tasks ela teve.
+    fn play_round(race: &mut Race) -> usize {
+        let tasks = race.next_round_tasks();
+        for task in &tasks {
+            race.record(task.id, fitness_of(&task.ag_config), Objective::Maximize);
+        }
+        tasks.len()
+    }
+
+    #[test]
+    fn rounds_alternate_graphs_and_share_the_instance() {
+        let mut race = race(config());
+        let first = race.next_round_tasks();
+        assert_eq!(first.len(), 3);
+        assert!(
+            first
+                .iter()
+                .all(|task| task.graph_id == "g1" && task.run_number == 0)
+        );
+        for task in &first {
+            race.record(task.id, 1.0, Objective::Maximize);
+        }
+        let second = race.next_round_tasks();
+        assert!(
+            second
+                .iter()
+                .all(|task| task.graph_id == "g2" && task.run_number == 0)
+        );
+    }
+
+    #[test]
+    fn consistently_worse_candidates_are_eliminated() {
+        let mut race = race(config().with_first_test(5));
+        for _ in 0..4 {
+            assert_eq!(play_round(&mut race), 3);
+        }
+        assert_eq!(race.survivors().count(), 3);
+
+        play_round(&mut race);
+        let survivors: Vec<&str> = race
+            .survivors()
+            .map(|candidate| candidate.ag_config.as_str())
+            .collect();
+        assert_eq!(survivors, vec!["good"]);
+        assert_eq!(race.best().unwrap().ag_config, "good");
+        assert_eq!(race.candidates()[2].eliminated_in_round, Some(5));
+
+        assert!(race.next_round_tasks().is_empty());
+        assert!(race.is_finished());
+    }
+
+    #[test]
+    fn identical_candidates_survive() {
+        let mut race = race(config().with_first_test(3));
+        for _ in 0..6 {
+            for task in race.next_round_tasks() {
+                race.record(task.id, 1.0, Objective::Maximize);
+            }
+        }
+        assert_eq!(race.survivors().count(), 3);
+        assert_eq!(race.rounds_completed(), 6);
+    }
+
+    #[test]
+    fn stops_when_the_budget_is_exhausted() {
+        let mut race = race(config().with_budget(5));
+        assert_eq!(play_round(&mut race), 3);
+        assert_eq!(play_round(&mut race), 0);
+        assert!(race.is_finished());
+        assert_eq!(race.evaluations(), 3);
+    }
+
+    #[test]
+    fn discarded_task_eliminates_its_candidate_and_closes_the_round() {
+        let mut race = race(config());
+        let tasks = race.next_round_tasks();
+        let (failed, others) = tasks.split_first().unwrap();
+        race.discard(failed.id);
+        assert!(!race.owns(failed.id));
+        for task in others {
+            race.record(task.id, 1.0, Objective::Maximize);
+        }
+        assert_eq!(race.rounds_completed(), 1);
+        assert_eq!(race.survivors().count(), 2);
+        assert_eq!(race.next_round_tasks().len(), 2);
+    }
+}
@@ -1,18 +1,138 @@
-use log::info;
-use serde::Serialize;
-use std::{collections::HashMap, error::Error, fs};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    fs,
+};
 use uuid::Uuid;
 
-use super::task_manager::{TaskManager, TaskStatus};
-use crate::common::TaskResult;
+use super::adaptive::{AdaptiveStop, ConfidenceInterval};
+use super::pareto::{ParetoFront, ParetoPoint};
+use super::sweep::configuration_key;
+use super::task_manager::{AbortRecord, TaskManager, TaskStatus};
+use super::verification::VerificationRecord;
+use crate::common::{Objective, ParameterAssignment, ProgressSample, Task, TaskResult};
 
 #[derive(Serialize)]
 struct ReportGraphDetails {
+    objective: Objective,
     results_collected: usize,
     best_fitness: f64,
     avg_processing_time_ms: f64,
     total_processing_time_ms: u64,
-    results: Vec<TaskResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pareto_front: Option<ReportParetoFront>,
+    results: Vec<ReportedResult>,
+}
+
+#[derive(Serialize)]
+struct ReportParetoFront {
+    directions: Vec<Objective>,
+    size: usize,
+    ideal: Vec<f64>,
+    nadir: Vec<f64>,
+    reference_point: Option<Vec<f64>>,
+    hypervolume: Option<f64>,
+    points: Vec<ParetoPoint>,
+}
+
+/// Resultado como gravado no relatório, com o que falta para reconstruir a
+/// task e reexecutá-la.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportedResult {
+    #[serde(flatten)]
+    pub result: TaskResult,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_number: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ag_config: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub objective: Option<Objective>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub objective_directions: Vec<Objective>,
+    #[serde(default, skip_serializing_if = "ParameterAssignment::is_empty")]
+    pub parameters: ParameterAssignment,
+    /// Curva de convergência publicada pelo runner durante a execução.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub convergence: Vec<ProgressSample>,
+}
+
+#[derive(Serialize)]
+struct ReportFitnessSummary {
+    results_collected: usize,
+    best_fitness: f64,
+    mean_fitness: f64,
+}
+
+impl ReportFitnessSummary {
+    fn from_samples(samples: &[f64], objective: Objective) -> Self {
+        let mean_fitness = if samples.is_empty() {
+            0.0
+        } else {
+            samples.iter().sum::<f64>() / samples.len() as f64
+        };
+        Self {
+            results_collected: samples.len(),
+            best_fitness: objective.best(samples.iter().copied()),
+            mean_fitness,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ReportConfiguration {
+    parameters: ParameterAssignment,
+    #[serde(flatten)]
+    overall: ReportFitnessSummary,
+    graphs: BTreeMap<String, ReportFitnessSummary>,
+}
+
+#[derive(Serialize)]
+struct ReportRaceCandidate {
+    ag_config: String,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    parameters: ParameterAssignment,
+    eliminated_in_round: Option<u32>,
+    rounds_run: usize,
+    mean_fitness: f64,
+}
+
+#[derive(Serialize)]
+struct ReportRace {
+    race_id: Uuid,
+    finished: bool,
+    rounds_completed: u32,
+    evaluations: usize,
+    best_ag_config: Option<String>,
+    candidates: Vec<ReportRaceCandidate>,
+}
+
+#[derive(Serialize)]
+struct ReportAdaptiveGraph {
+    graph_id: String,
+    runs_created: u32,
+    min_runs: u32,
+    max_runs: u32,
+    target_width: f64,
+    confidence: f64,
+    interval: Option<ConfidenceInterval>,
+    stopped: Option<AdaptiveStop>,
+}
+
+#[derive(Serialize)]
+struct ReportWorkerPair {
+    original_worker: Uuid,
+    verifying_worker: Uuid,
+    mismatches: usize,
+}
+
+#[derive(Serialize)]
+struct ReportVerification {
+    checked: usize,
+    mismatches: Vec<VerificationRecord>,
+    mismatched_worker_pairs: Vec<ReportWorkerPair>,
 }
 
 #[derive(Serialize)]
@@ -22,6 +142,8 @@ struct ReportStatusSummary {
     failed: usize,
     pending: usize,
     assigned: usize,
+    aborted: usize,
+    waiting: usize,
 }
 
 #[derive(Serialize, Clone)]
@@ -34,80 +156,343 @@ struct WorkerReport {
 
 #[derive(Serialize)]
 struct JsonReport {
+    job_id: String,
+    master_seed: Option<u64>,
+    objective: Objective,
     task_summary: ReportStatusSummary,
+    aborts: Vec<AbortRecord>,
     graphs: HashMap<String, ReportGraphDetails>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    configurations: Vec<ReportConfiguration>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    races: Vec<ReportRace>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    adaptive_graphs: Vec<ReportAdaptiveGraph>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    verification: Option<ReportVerification>,
     workers: Vec<WorkerReport>, // Novo campo para estatísticas dos workers
 }
 
-pub struct ResultAggregator {
+/// Fitness obtidos por uma configuração de uma varredura, separados por graph.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigurationResults {
+    pub parameters: ParameterAssignment,
+    pub fitness_by_graph: BTreeMap<String, Vec<f64>>,
+}
+
+impl ConfigurationResults {
+    #[must_use]
+    pub fn all_fitness(&self) -> Vec<f64> {
+        self.fitness_by_graph.values().flatten().copied().collect()
+    }
+}
+
+struct RecordedTask {
+    run_number: u32,
+    ag_config: String,
+    objective: Objective,
+    objective_directions: Vec<Objective>,
+    parameters: ParameterAssignment,
+    convergence: Vec<ProgressSample>,
+}
+
+/// Amostras guardadas por curva de convergência. Ao passar disso a curva é
+/// reduzida à metade e passa a guardar uma a cada duas amostras a mais.
+const MAX_CONVERGENCE_SAMPLES: usize = 1024;
+
+/// Curva de uma task ainda em execução, do worker que a detém.
+struct LiveProgress {
+    worker_id: Uuid,
+    samples: Vec<ProgressSample>,
+    /// Só uma a cada `stride` amostras recebidas entra na curva.
+    stride: usize,
+    received: usize,
+    /// A última amostra fica no fim da curva mesmo fora do passo, até ser
+    /// substituída pela próxima.
+    last_is_extra: bool,
+}
+
+impl LiveProgress {
+    const fn new(worker_id: Uuid) -> Self {
+        Self {
+            worker_id,
+            samples: Vec::new(),
+            stride: 1,
+            received: 0,
+            last_is_extra: false,
+        }
+    }
+
+    fn push(&mut self, sample: ProgressSample) {
+        if self.last_is_extra {
+            self.samples.pop();
+        }
+        self.last_is_extra = !self.received.is_multiple_of(self.stride);
+        self.received += 1;
+        self.samples.push(sample);
+
+        if self.samples.len() > MAX_CONVERGENCE_SAMPLES {
+            let mut index = 0;
+            self.samples.retain(|_| {
+                index += 1;
+                index % 2 == 1
+            });
+            self.stride *= 2;
+        }
+    }
+}
+
+#[derive(Default)]
+struct JobResults {
     results_by_graph: HashMap<String, Vec<TaskResult>>,
+    tasks: HashMap<Uuid, RecordedTask>,
+    progress: HashMap<Uuid, LiveProgress>,
+    pareto_fronts: HashMap<String, ParetoFront>,
+    configurations: BTreeMap<String, ConfigurationResults>,
     total_results_collected: usize,
 }
 
+pub struct ResultAggregator {
+    jobs: HashMap<String, JobResults>,
+}
+
 impl ResultAggregator {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            results_by_graph: HashMap::new(),
-            total_results_collected: 0,
+            jobs: HashMap::new(),
         }
     }
 
-    pub fn add_result(&mut self, result: TaskResult) -> Result<(), Box<dyn Error>> {
+    pub fn add_result(&mut self, job_id: &str, result: TaskResult) -> Result<(), Box<dyn Error>> {
+        let job = match self.jobs.get_mut(job_id) {
+            Some(job) => job,
+            None => self.jobs.entry(job_id.to_string()).or_default(),
+        };
+
         let graph_id = result.graph_id.clone();
-        self.results_by_graph
+        job.results_by_graph
             .entry(graph_id)
             .or_default()
             .push(result);
 
-        self.total_results_collected += 1;
+        job.total_results_collected += 1;
         info!(
-            "Resultado adicionado. total de resultados: {}",
-            self.total_results_collected
+            "Resultado adicionado ao job {job_id}. total de resultados: {}",
+            job.total_results_collected
         );
 
         Ok(())
     }
 
+    /// Adiciona o resultado e, se a task veio de uma varredura, agrupa o
+    /// fitness pela configuração de parâmetros.
+    pub fn add_task_result(
+        &mut self,
+        task: &Task,
+        mut result: TaskResult,
+    ) -> Result<(), Box<dyn Error>> {
+        // O host é quem conhece a semente; não depende do runner preenchê-la.
+        result.seed = task.seed;
+        let job = match self.jobs.get_mut(&task.job_id) {
+            Some(job) => job,
+            None => self.jobs.entry(task.job_id.clone()).or_default(),
+        };
+        let convergence = job
+            .progress
+            .remove(&task.id)
+            .filter(|progress| progress.worker_id == result.worker_id)
+            .map(|progress| progress.samples)
+            .unwrap_or_default();
+        job.tasks.insert(
+            task.id,
+            RecordedTask {
+                run_number: task.run_number,
+                ag_config: task.ag_config.clone(),
+                objective: task.objective,
+                objective_directions: task.objective_directions.clone(),
+                parameters: task.parameters.clone(),
+                convergence,
+            },
+        );
+        if !result.objectives.is_empty() {
+            let dimensions = result.objectives.len();
+            let front = job
+                .pareto_fronts
+                .entry(result.graph_id.clone())
+                .or_insert_with(|| ParetoFront::new(task.directions_for(dimensions)));
+            if front.directions().len() == dimensions {
+                front.insert(ParetoPoint {
+                    task_id: result.task_id,
+                    worker_id: result.worker_id,
+                    objectives: result.objectives.clone(),
+                });
+            } else {
+                warn!(
+                    "Resultado da task {} tem {dimensions} objetivos, mas o graph {} usa {}",
+                    result.task_id,
+                    result.graph_id,
+                    front.directions().len()
+                );
+            }
+        }
+        if !task.parameters.is_empty() {
+            job.configurations
+                .entry(configuration_key(&task.parameters))
+                .or_insert_with(|| ConfigurationResults {
+                    parameters: task.parameters.clone(),
+                    fitness_by_graph: BTreeMap::new(),
+                })
+                .fitness_by_graph
+                .entry(result.graph_id.clone())
+                .or_default()
+                .push(result.fitness);
+        }
+        self.add_result(&task.job_id, result)
+    }
+
+    /// Acrescenta amostras à curva de convergência de uma task em execução. Se
+    /// a task foi reatribuída, a curva recomeça com o novo worker.
+    pub fn record_progress(
+        &mut self,
+        job_id: &str,
+        task_id: Uuid,
+        worker_id: Uuid,
+        samples: Vec<ProgressSample>,
+    ) {
+        let job = match self.jobs.get_mut(job_id) {
+            Some(job) => job,
+            None => self.jobs.entry(job_id.to_string()).or_default(),
+        };
+        let progress = job
+            .progress
+            .entry(task_id)
+            .or_insert_with(|| LiveProgress::new(worker_id));
+        if progress.worker_id != worker_id {
+            *progress = LiveProgress::new(worker_id);
+        }
+        for sample in samples {
+            progress.push(sample);
+        }
+    }
+
+    /// Descarta as curvas de tasks que não estão mais com o worker que as
+    /// reportou: abortadas, devolvidas à fila ou que falharam.
+    pub fn discard_stale_progress(&mut self, task_manager: &TaskManager) {
+        for job in self.jobs.values_mut() {
+            job.progress.retain(|&task_id, progress| {
+                task_manager
+                    .get_assigned_task(task_id, progress.worker_id)
+                    .is_some()
+            });
+        }
+    }
+
+    /// Curva de convergência de uma task, em execução ou concluída.
+    #[must_use]
+    pub fn get_convergence(&self, job_id: &str, task_id: Uuid) -> Option<&[ProgressSample]> {
+        let job = self.jobs.get(job_id)?;
+        job.progress
+            .get(&task_id)
+            .map(|progress| progress.samples.as_slice())
+            .or_else(|| {
+                job.tasks
+                    .get(&task_id)
+                    .map(|task| task.convergence.as_slice())
+            })
+    }
+
+    /// Resultados agrupados por configuração, indexados pela chave canônica
+    /// dos parâmetros.
     #[must_use]
-    pub const fn get_results_collected(&self) -> usize {
-        self.total_results_collected
+    pub fn get_configuration_results(
+        &self,
+        job_id: &str,
+    ) -> Option<&BTreeMap<String, ConfigurationResults>> {
+        self.jobs.get(job_id).map(|job| &job.configurations)
     }
 
+    /// Frente mantida conforme os resultados chegam, com as direções da
+    /// primeira task do graph que reportou objetivos.
     #[must_use]
-    pub const fn get_all_results(&self) -> &HashMap<String, Vec<TaskResult>> {
-        &self.results_by_graph
+    pub fn get_pareto_front(&self, job_id: &str, graph_id: &str) -> Option<&ParetoFront> {
+        self.jobs
+            .get(job_id)
+            .and_then(|job| job.pareto_fronts.get(graph_id))
+    }
+
+    /// Frente com as direções configuradas no `TaskManager`. Se elas mudaram
+    /// depois dos primeiros resultados, a frente é refeita a partir de todos
+    /// os resultados do graph.
+    #[must_use]
+    pub fn pareto_front(
+        &self,
+        task_manager: &TaskManager,
+        job_id: &str,
+        graph_id: &str,
+    ) -> Option<Cow<'_, ParetoFront>> {
+        let front = self.get_pareto_front(job_id, graph_id)?;
+        let directions =
+            task_manager.get_objective_directions(job_id, graph_id, front.directions().len());
+        if front.directions() == directions.as_slice() {
+            return Some(Cow::Borrowed(front));
+        }
+
+        let mut rebuilt = ParetoFront::new(directions);
+        let results = self
+            .get_all_results(job_id)
+            .and_then(|results| results.get(graph_id))
+            .map_or(&[][..], Vec::as_slice);
+        for result in results {
+            rebuilt.insert(ParetoPoint {
+                task_id: result.task_id,
+                worker_id: result.worker_id,
+                objectives: result.objectives.clone(),
+            });
+        }
+        Some(Cow::Owned(rebuilt))
+    }
+
+    #[must_use]
+    pub fn get_job_ids(&self) -> Vec<&str> {
+        self.jobs.keys().map(String::as_str).collect()
+    }
+
+    #[must_use]
+    pub fn get_results_collected(&self, job_id: &str) -> usize {
+        self.jobs
+            .get(job_id)
+            .map_or(0, |job| job.total_results_collected)
+    }
+
+    #[must_use]
+    pub fn get_all_results(&self, job_id: &str) -> Option<&HashMap<String, Vec<TaskResult>>> {
+        self.jobs.get(job_id).map(|job| &job.results_by_graph)
     }
 
     pub fn generate_and_save_report(
         &self,
         task_manager: &TaskManager,
+        job_id: &str,
         file_path: &str,
     ) -> Result<(), Box<dyn Error>> {
-        info!("Gerando relatório final para {}", file_path);
+        info!("Gerando relatório final do job {job_id} para {file_path}");
 
         let task_summary = ReportStatusSummary {
-            total: task_manager.get_total_tasks(),
-            completed: task_manager.get_completed_tasks_count(),
-            failed: task_manager
-                .get_tasks_status()
-                .values()
-                .filter(|&&s| s == TaskStatus::Failed)
-                .count(),
-            pending: task_manager
-                .get_tasks_status()
-                .values()
-                .filter(|&&s| s == TaskStatus::Pending)
-                .count(),
-            assigned: task_manager
-                .get_tasks_status()
-                .values()
-                .filter(|&&s| s == TaskStatus::Assigned)
-                .count(),
+            total: task_manager.get_total_tasks(job_id),
+            completed: task_manager.get_completed_tasks_count(job_id),
+            failed: task_manager.count_tasks_with_status(job_id, TaskStatus::Failed),
+            pending: task_manager.count_tasks_with_status(job_id, TaskStatus::Pending),
+            assigned: task_manager.count_tasks_with_status(job_id, TaskStatus::Assigned),
+            aborted: task_manager.count_tasks_with_status(job_id, TaskStatus::Aborted),
+            waiting: task_manager.count_tasks_with_status(job_id, TaskStatus::Waiting),
         };
 
-        let graphs: HashMap<String, ReportGraphDetails> = self
-            .get_all_results()
+        let empty = HashMap::new();
+        let all_results = self.get_all_results(job_id).unwrap_or(&empty);
+        let recorded_tasks = self.jobs.get(job_id).map(|job| &job.tasks);
+
+        let graphs: HashMap<String, ReportGraphDetails> = all_results
             .iter()
             .map(|(graph_id, results)| {
                 let total_time_ms: u64 = results.iter().map(|r| r.processing_time_ms).sum();
@@ -116,26 +501,165 @@ impl ResultAggregator {
                 } else {
                     total_time_ms as f64 / results.len() as f64
                 };
-                let best_fitness = results
-                    .iter()
-                    .map(|r| r.fitness)
-                    .fold(f64::NEG_INFINITY, f64::max);
+                let objective = task_manager.get_objective(job_id, graph_id);
+                let best_fitness = objective.best(results.iter().map(|r| r.fitness));
+                let pareto_front = self.pareto_front(task_manager, job_id, graph_id).map(|front| {
+                    let reference_point = task_manager
+                        .get_reference_point(job_id, graph_id)
+                        .map(<[f64]>::to_vec);
+                    let hypervolume = reference_point.as_deref().and_then(|reference| {
+                        let hypervolume = front.hypervolume(reference);
+                        if hypervolume.is_none() {
+                            warn!(
+                                "Ponto de referência do graph {graph_id} tem {} valores, mas a frente tem {} objetivos",
+                                reference.len(),
+                                front.directions().len()
+                            );
+                        }
+                        hypervolume
+                    });
+                    ReportParetoFront {
+                        directions: front.directions().to_vec(),
+                        size: front.points().len(),
+                        ideal: front.ideal(),
+                        nadir: front.nadir(),
+                        hypervolume,
+                        reference_point,
+                        points: front.points().to_vec(),
+                    }
+                });
 
                 (
                     graph_id.clone(),
                     ReportGraphDetails {
+                        objective,
                         results_collected: results.len(),
                         best_fitness,
                         avg_processing_time_ms: avg_time_ms,
                         total_processing_time_ms: total_time_ms,
-                        results: results.clone(),
+                        pareto_front,
+                        results: results
+                            .iter()
+                            .map(|result| {
+                                let task =
+                                    recorded_tasks.and_then(|tasks| tasks.get(&result.task_id));
+                                ReportedResult {
+                                    result: result.clone(),
+                                    run_number: task.map(|task| task.run_number),
+                                    ag_config: task.map(|task| task.ag_config.clone()),
+                                    objective: task.map(|task| task.objective),
+                                    objective_directions: task
+                                        .map(|task| task.objective_directions.clone())
+                                        .unwrap_or_default(),
+                                    parameters: task
+                                        .map(|task| task.parameters.clone())
+                                        .unwrap_or_default(),
+                                    convergence: task
+                                        .map(|task| task.convergence.clone())
+                                        .unwrap_or_default(),
+                                }
+                            })
+                            .collect(),
                     },
                 )
             })
             .collect();
 
+        let configurations: Vec<ReportConfiguration> = self
+            .get_configuration_results(job_id)
+            .into_iter()
+            .flat_map(BTreeMap::values)
+            .map(|configuration| ReportConfiguration {
+                parameters: configuration.parameters.clone(),
+                overall: ReportFitnessSummary::from_samples(
+                    &configuration.all_fitness(),
+                    task_manager.get_job_objective(job_id),
+                ),
+                graphs: configuration
+                    .fitness_by_graph
+                    .iter()
+                    .map(|(graph_id, samples)| {
+                        (
+                            graph_id.clone(),
+                            ReportFitnessSummary::from_samples(
+                                samples,
+                                task_manager.get_objective(job_id, graph_id),
+                            ),
+                        )
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        let races: Vec<ReportRace> = task_manager
+            .get_races(job_id)
+            .into_iter()
+            .map(|race| ReportRace {
+                race_id: race.id(),
+                finished: race.is_finished(),
+                rounds_completed: race.rounds_completed(),
+                evaluations: race.evaluations(),
+                best_ag_config: race.best().map(|candidate| candidate.ag_config.clone()),
+                candidates: race
+                    .candidates()
+                    .iter()
+                    .map(|candidate| ReportRaceCandidate {
+                        ag_config: candidate.ag_config.clone(),
+                        parameters: candidate.parameters.clone(),
+                        eliminated_in_round: candidate.eliminated_in_round,
+                        rounds_run: candidate.fitness.len(),
+                        mean_fitness: candidate.mean_fitness(),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        let adaptive_graphs: Vec<ReportAdaptiveGraph> = task_manager
+            .get_adaptive_graphs(job_id)
+            .into_iter()
+            .map(|adaptive| ReportAdaptiveGraph {
+                graph_id: adaptive.graph_id.clone(),
+                runs_created: adaptive.runs_created,
+                min_runs: adaptive.config.min_runs,
+                max_runs: adaptive.config.max_runs,
+                target_width: adaptive.config.target_width,
+                confidence: adaptive.config.confidence,
+                interval: adaptive.interval,
+                stopped: adaptive.stopped,
+            })
+            .collect();
+
+        let verifications = task_manager.get_verifications(job_id);
+        let verification = (!verifications.is_empty()).then(|| {
+            let mismatches: Vec<VerificationRecord> = verifications
+                .iter()
+                .filter(|record| !record.is_match())
+                .cloned()
+                .collect();
+            let mut pairs: BTreeMap<(Uuid, Uuid), usize> = BTreeMap::new();
+            for record in &mismatches {
+                *pairs
+                    .entry((record.original_worker, record.verifying_worker))
+                    .or_default() += 1;
+            }
+            ReportVerification {
+                checked: verifications.len(),
+                mismatches,
+                mismatched_worker_pairs: pairs
+                    .into_iter()
+                    .map(
+                        |((original_worker, verifying_worker), mismatches)| ReportWorkerPair {
+                            original_worker,
+                            verifying_worker,
+                            mismatches,
+                        },
+                    )
+                    .collect(),
+            }
+        });
+
         let mut worker_stats: HashMap<Uuid, (u32, u64)> = HashMap::new();
-        for results in self.get_all_results().values() {
+        for results in all_results.values() {
             for result in results {
                 let stats = worker_stats.entry(result.worker_id).or_insert((0, 0));
                 stats.0 += 1;
@@ -161,8 +685,16 @@ impl ResultAggregator {
             .collect();
 
         let report = JsonReport {
+            job_id: job_id.to_string(),
+            master_seed: task_manager.get_job_seed(job_id),
+            objective: task_manager.get_job_objective(job_id),
             task_summary,
+            aborts: task_manager.get_aborts(job_id).to_vec(),
             graphs,
+            configurations,
+            races,
+            adaptive_graphs,
+            verification,
             workers,
         };
 
@@ -180,3 +712,45 @@ impl Default for ResultAggregator {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(generation: u32) -> ProgressSample {
+        ProgressSample {
+            generation,
+            best_fitness: f64::from(generation),
+            mean_fitness: 0.0,
+            elapsed_ms: 0,
+        }
+    }
+
+    #[test]
+    fn live_progress_is_capped_and_keeps_the_last_sample() {
+        let mut progress = LiveProgress::new(Uuid::nil());
+        for generation in 0..10_000 {
+            progress.push(sample(generation));
+        }
+        let generations: Vec<u32> = progress.samples.iter().map(|s| s.generation).collect();
+
+        assert!(generations.len() <= MAX_CONVERGENCE_SAMPLES);
+        assert!(generations.len() > MAX_CONVERGENCE_SAMPLES / 2);
+        assert_eq!(generations.first(), Some(&0));
+        assert_eq!(generations.last(), Some(&9_999));
+        // Fora a última, as amostras seguem um passo uniforme.
+        let stride = generations[1] - generations[0];
+        let (last, strided) = generations.split_last().unwrap();
+        assert!(strided.windows(2).all(|pair| pair[1] - pair[0] == stride));
+        assert!(last - strided.last().unwrap() <= stride);
+    }
+
+    #[test]
+    fn live_progress_below_the_cap_keeps_every_sample() {
+        let mut progress = LiveProgress::new(Uuid::nil());
+        for generation in 0..100 {
+            progress.push(sample(generation));
+        }
+        assert_eq!(progress.samples.len(), 100);
+    }
+}
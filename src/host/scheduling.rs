@@ -0,0 +1,976 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+use rand::seq::IteratorRandom;
+use uuid::Uuid;
+
+use crate::common::{Task, WorkerCapabilities};
+
+const WORKER_HISTORY_LEN: usize = 32;
+
+/// Metadados e histórico recente de um worker, mantidos pelo `TaskManager`.
+#[derive(Debug, Clone)]
+pub struct WorkerInfo {
+    pub worker_id: Uuid,
+    pub tasks_assigned: u64,
+    pub tasks_completed: u64,
+    pub tasks_failed: u64,
+    pub capabilities: WorkerCapabilities,
+    /// Graphs das últimas tasks atribuídas, da mais antiga para a mais recente.
+    pub recent_graphs: VecDeque<String>,
+    pub first_seen: Instant,
+    pub last_seen: Instant,
+}
+
+impl WorkerInfo {
+    pub fn new(worker_id: Uuid) -> Self {
+        let now = Instant::now();
+        Self {
+            worker_id,
+            tasks_assigned: 0,
+            tasks_completed: 0,
+            tasks_failed: 0,
+            capabilities: WorkerCapabilities::default(),
+            recent_graphs: VecDeque::with_capacity(WORKER_HISTORY_LEN),
+            first_seen: now,
+            last_seen: now,
+        }
+    }
+
+    #[must_use]
+    pub fn last_graph(&self) -> Option<&str> {
+        self.recent_graphs.back().map(String::as_str)
+    }
+
+    pub(crate) fn record_assignment(&mut self, task: &Task) {
+        self.tasks_assigned += 1;
+        self.last_seen = Instant::now();
+        if self.recent_graphs.len() == WORKER_HISTORY_LEN {
+            self.recent_graphs.pop_front();
+        }
+        self.recent_graphs.push_back(task.graph_id.clone());
+    }
+}
+
+/// Valores por graph, separados por job, já que jobs diferentes podem usar o
+/// mesmo id de graph.
+#[derive(Debug, Clone)]
+pub(crate) struct PerGraph<V> {
+    by_job: HashMap<String, HashMap<String, V>>,
+}
+
+impl<V> Default for PerGraph<V> {
+    fn default() -> Self {
+        Self {
+            by_job: HashMap::new(),
+        }
+    }
+}
+
+impl<V> PerGraph<V> {
+    pub(crate) fn get(&self, job_id: &str, graph_id: &str) -> Option<&V> {
+        self.by_job.get(job_id)?.get(graph_id)
+    }
+
+    pub(crate) fn get_mut(&mut self, job_id: &str, graph_id: &str) -> Option<&mut V> {
+        self.by_job.get_mut(job_id)?.get_mut(graph_id)
+    }
+
+    pub(crate) fn insert(&mut self, job_id: &str, graph_id: &str, value: V) {
+        self.by_job
+            .entry(job_id.to_string())
+            .or_default()
+            .insert(graph_id.to_string(), value);
+    }
+
+    pub(crate) fn entry(&mut self, job_id: &str, graph_id: &str) -> &mut V
+    where
+        V: Default,
+    {
+        if self.get(job_id, graph_id).is_none() {
+            self.insert(job_id, graph_id, V::default());
+        }
+        self.get_mut(job_id, graph_id)
+            .expect("valor inserido logo acima")
+    }
+
+    pub(crate) fn values(&self) -> impl Iterator<Item = &V> {
+        self.by_job.values().flat_map(HashMap::values)
+    }
+}
+
+/// Prioridades por graph e envelhecimento das tasks pendentes.
+///
+/// A prioridade efetiva de uma task é a soma da sua prioridade, da prioridade
+/// do seu graph e de um ponto por intervalo de `aging` esperado na fila.
+#[derive(Debug, Default)]
+pub struct Priorities {
+    graph_priorities: PerGraph<i32>,
+    pending_since: HashMap<Uuid, Instant>,
+    aging: Option<Duration>,
+    in_use: bool,
+}
+
+impl Priorities {
+    #[must_use]
+    pub fn graph_priority(&self, job_id: &str, graph_id: &str) -> i32 {
+        self.graph_priorities
+            .get(job_id, graph_id)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    #[must_use]
+    pub fn effective_priority(&self, task: &Task, now: Instant) -> i64 {
+        let age_bonus = match (self.aging, self.pending_since.get(&task.id)) {
+            (Some(aging), Some(since)) if !aging.is_zero() => {
+                (now.saturating_duration_since(*since).as_nanos() / aging.as_nanos()) as i64
+            }
+            _ => 0,
+        };
+        i64::from(task.priority)
+            + i64::from(self.graph_priority(&task.job_id, &task.graph_id))
+            + age_bonus
+    }
+
+    /// Falso enquanto nenhuma prioridade ou envelhecimento foi configurado,
+    /// permitindo pular o cálculo de prioridades.
+    #[must_use]
+    pub const fn in_use(&self) -> bool {
+        self.in_use
+    }
+
+    pub(crate) fn set_graph_priority(&mut self, job_id: &str, graph_id: &str, priority: i32) {
+        self.graph_priorities.insert(job_id, graph_id, priority);
+        self.in_use = true;
+    }
+
+    /// `tasks_prioritized` indica se alguma task pendente tem prioridade
+    /// própria; sem ela, sem envelhecimento e sem prioridade de graph o
+    /// cálculo volta a ser pulado.
+    pub(crate) fn set_aging(&mut self, aging: Option<Duration>, tasks_prioritized: bool) {
+        self.aging = aging;
+        self.in_use = aging.is_some()
+            || tasks_prioritized
+            || self
+                .graph_priorities
+                .values()
+                .any(|&priority| priority != 0);
+    }
+
+    pub(crate) fn mark_in_use(&mut self) {
+        self.in_use = true;
+    }
+
+    pub(crate) fn enqueued(&mut self, task_id: Uuid) {
+        self.pending_since.insert(task_id, Instant::now());
+    }
+
+    pub(crate) fn dequeued(&mut self, task_id: Uuid) {
+        self.pending_since.remove(&task_id);
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct RuntimeStats {
+    runs: u64,
+    total_ms: u64,
+}
+
+impl RuntimeStats {
+    fn record(&mut self, processing_time_ms: u64) {
+        self.runs += 1;
+        self.total_ms += processing_time_ms;
+    }
+
+    fn mean_ms(self) -> Option<f64> {
+        (self.runs > 0).then(|| self.total_ms as f64 / self.runs as f64)
+    }
+}
+
+/// Tempos de processamento das execuções concluídas, por graph e por config.
+#[derive(Debug, Default)]
+pub struct RuntimeEstimates {
+    by_config: PerGraph<HashMap<String, RuntimeStats>>,
+    by_graph: PerGraph<RuntimeStats>,
+}
+
+impl RuntimeEstimates {
+    pub fn record(
+        &mut self,
+        job_id: &str,
+        graph_id: &str,
+        ag_config: &str,
+        processing_time_ms: u64,
+    ) {
+        let configs = self.by_config.entry(job_id, graph_id);
+        match configs.get_mut(ag_config) {
+            Some(stats) => stats.record(processing_time_ms),
+            None => configs
+                .entry(ag_config.to_string())
+                .or_default()
+                .record(processing_time_ms),
+        }
+
+        self.by_graph
+            .entry(job_id, graph_id)
+            .record(processing_time_ms);
+    }
+
+    /// Média das execuções com o mesmo graph e config; sem nenhuma, usa a
+    /// média do graph com qualquer config.
+    #[must_use]
+    pub fn estimate_ms(&self, task: &Task) -> Option<f64> {
+        self.by_config
+            .get(&task.job_id, &task.graph_id)
+            .and_then(|configs| configs.get(&task.ag_config))
+            .and_then(|stats| stats.mean_ms())
+            .or_else(|| {
+                self.by_graph
+                    .get(&task.job_id, &task.graph_id)
+                    .and_then(|stats| stats.mean_ms())
+            })
+    }
+}
+
+/// Jobs e graphs cujas tasks não devem ser atribuídas no momento.
+#[derive(Debug, Default)]
+pub struct PausedWork {
+    jobs: HashSet<String>,
+    graphs: HashMap<String, HashSet<String>>,
+}
+
+impl PausedWork {
+    #[must_use]
+    pub fn is_job_paused(&self, job_id: &str) -> bool {
+        self.jobs.contains(job_id)
+    }
+
+    #[must_use]
+    pub fn is_graph_paused(&self, job_id: &str, graph_id: &str) -> bool {
+        self.graphs
+            .get(job_id)
+            .is_some_and(|graphs| graphs.contains(graph_id))
+    }
+
+    #[must_use]
+    pub fn is_paused(&self, task: &Task) -> bool {
+        self.is_job_paused(&task.job_id) || self.is_graph_paused(&task.job_id, &task.graph_id)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty() && self.graphs.is_empty()
+    }
+
+    pub(crate) fn pause_job(&mut self, job_id: &str) -> bool {
+        self.jobs.insert(job_id.to_string())
+    }
+
+    pub(crate) fn resume_job(&mut self, job_id: &str) -> bool {
+        self.jobs.remove(job_id)
+    }
+
+    pub(crate) fn pause_graph(&mut self, job_id: &str, graph_id: &str) -> bool {
+        self.graphs
+            .entry(job_id.to_string())
+            .or_default()
+            .insert(graph_id.to_string())
+    }
+
+    pub(crate) fn resume_graph(&mut self, job_id: &str, graph_id: &str) -> bool {
+        let Some(graphs) = self.graphs.get_mut(job_id) else {
+            return false;
+        };
+        let resumed = graphs.remove(graph_id);
+        if graphs.is_empty() {
+            self.graphs.remove(job_id);
+        }
+        resumed
+    }
+}
+
+/// Quantas tasks de um graph estão em execução e quantas já foram concluídas.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GraphLoad {
+    pub running: usize,
+    pub completed: usize,
+}
+
+/// Visão que uma política tem do estado do `TaskManager` ao escolher uma task.
+pub struct SchedulingContext<'a> {
+    pending: &'a VecDeque<Task>,
+    worker: &'a WorkerInfo,
+    priorities: &'a Priorities,
+    graph_loads: &'a PerGraph<GraphLoad>,
+    runtimes: &'a RuntimeEstimates,
+    paused: &'a PausedWork,
+    now: Instant,
+    /// Índices das candidatas, calculados uma vez por escolha quando há
+    /// prioridades em uso.
+    top_priority: Option<Vec<usize>>,
+}
+
+impl<'a> SchedulingContext<'a> {
+    pub(crate) fn new(
+        pending: &'a VecDeque<Task>,
+        worker: &'a WorkerInfo,
+        priorities: &'a Priorities,
+        graph_loads: &'a PerGraph<GraphLoad>,
+        runtimes: &'a RuntimeEstimates,
+        paused: &'a PausedWork,
+    ) -> Self {
+        let now = Instant::now();
+        // Só as tasks com a maior prioridade efetiva, entre as que o worker
+        // pode executar agora, são candidatas.
+        let top_priority = priorities.in_use().then(|| {
+            let mut best = i64::MIN;
+            let mut indices = Vec::new();
+            for (index, task) in pending.iter().enumerate() {
+                if !Self::is_eligible(task, worker, paused) {
+                    continue;
+                }
+                let priority = priorities.effective_priority(task, now);
+                if priority > best {
+                    best = priority;
+                    indices.clear();
+                }
+                if priority == best {
+                    indices.push(index);
+                }
+            }
+            indices
+        });
+
+        Self {
+            pending,
+            worker,
+            priorities,
+            graph_loads,
+            runtimes,
+            paused,
+            now,
+            top_priority,
+        }
+    }
+
+    fn is_eligible(task: &Task, worker: &WorkerInfo, paused: &PausedWork) -> bool {
+        task.requirements.is_satisfied_by(&worker.capabilities)
+            && task.requirements.allows_worker(worker.worker_id)
+            && !paused.is_paused(task)
+    }
+
+    #[must_use]
+    pub const fn pending(&self) -> &'a VecDeque<Task> {
+        self.pending
+    }
+
+    #[must_use]
+    pub const fn worker(&self) -> &'a WorkerInfo {
+        self.worker
+    }
+
+    #[must_use]
+    pub fn graph_load(&self, job_id: &str, graph_id: &str) -> GraphLoad {
+        self.graph_loads
+            .get(job_id, graph_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn estimated_runtime_ms(&self, task: &Task) -> Option<f64> {
+        self.runtimes.estimate_ms(task)
+    }
+
+    #[must_use]
+    pub fn effective_priority(&self, task: &Task) -> i64 {
+        self.priorities.effective_priority(task, self.now)
+    }
+
+    /// Tasks que podem ser entregues ao worker, com seus índices em `pending`.
+    pub fn candidates(&self) -> impl DoubleEndedIterator<Item = (usize, &'a Task)> + '_ {
+        let pending = self.pending;
+        let worker = self.worker;
+        let paused = self.paused;
+        // Com prioridades em uso as candidatas já foram calculadas; sem elas a
+        // fila é filtrada sob demanda, e a Fifo para na primeira.
+        let unranked_len = if self.top_priority.is_some() {
+            0
+        } else {
+            pending.len()
+        };
+
+        let top_priority = self
+            .top_priority
+            .iter()
+            .flatten()
+            .map(move |&index| (index, &pending[index]));
+        let unranked = pending
+            .range(..unranked_len)
+            .enumerate()
+            .filter(move |(_, task)| Self::is_eligible(task, worker, paused));
+        top_priority.chain(unranked)
+    }
+}
+
+pub trait SchedulingPolicy: Send + 'static {
+    /// Retorna o índice, em `ctx.pending()`, da task a ser atribuída ao worker.
+    /// O índice precisa vir de `ctx.candidates()`.
+    fn select(&mut self, ctx: &SchedulingContext<'_>) -> Option<usize>;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Fifo;
+
+impl SchedulingPolicy for Fifo {
+    fn select(&mut self, ctx: &SchedulingContext<'_>) -> Option<usize> {
+        ctx.candidates().next().map(|(index, _)| index)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Lifo;
+
+impl SchedulingPolicy for Lifo {
+    fn select(&mut self, ctx: &SchedulingContext<'_>) -> Option<usize> {
+        ctx.candidates().next_back().map(|(index, _)| index)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Random;
+
+impl SchedulingPolicy for Random {
+    fn select(&mut self, ctx: &SchedulingContext<'_>) -> Option<usize> {
+        ctx.candidates()
+            .choose(&mut rand::rng())
+            .map(|(index, _)| index)
+    }
+}
+
+/// Distribui os workers entre os graphs com tasks pendentes, para que todos
+/// progridam ao mesmo tempo em vez de um graph monopolizar o cluster.
+#[derive(Debug, Clone)]
+pub struct FairShare {
+    mode: FairShareMode,
+}
+
+#[derive(Debug, Clone)]
+enum FairShareMode {
+    Weighted(PerGraph<f64>),
+    RoundRobin {
+        last_graph: Option<(String, String)>,
+    },
+}
+
+impl FairShare {
+    /// Escolhe o graph com menos tasks em execução; empates vão para o graph
+    /// com menos tasks concluídas.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::weighted(HashMap::new())
+    }
+
+    /// Como `new`, mas a carga de cada graph é dividida pelo seu peso (padrão 1.0),
+    /// então um graph com peso 2 recebe o dobro de workers. Os pesos são
+    /// indexados por `(job_id, graph_id)`.
+    #[must_use]
+    pub fn weighted(weights: HashMap<(String, String), f64>) -> Self {
+        let mut by_graph = PerGraph::default();
+        for ((job_id, graph_id), weight) in weights {
+            by_graph.insert(&job_id, &graph_id, weight);
+        }
+        Self {
+            mode: FairShareMode::Weighted(by_graph),
+        }
+    }
+
+    /// Alterna entre os graphs em ordem alfabética de job e graph, uma task
+    /// por vez.
+    #[must_use]
+    pub fn round_robin() -> Self {
+        Self {
+            mode: FairShareMode::RoundRobin { last_graph: None },
+        }
+    }
+}
+
+impl Default for FairShare {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SchedulingPolicy for FairShare {
+    fn select(&mut self, ctx: &SchedulingContext<'_>) -> Option<usize> {
+        // Primeira task candidata de cada graph, preservando a ordem FIFO dentro dele.
+        let mut first_by_graph: HashMap<(&str, &str), usize> = HashMap::new();
+        for (index, task) in ctx.candidates() {
+            first_by_graph
+                .entry((task.job_id.as_str(), task.graph_id.as_str()))
+                .or_insert(index);
+        }
+
+        match &mut self.mode {
+            FairShareMode::Weighted(weights) => {
+                let share = |job_id: &str, graph_id: &str, count: usize| {
+                    let weight = weights.get(job_id, graph_id).copied().unwrap_or(1.0);
+                    if weight > 0.0 {
+                        count as f64 / weight
+                    } else {
+                        f64::INFINITY
+                    }
+                };
+
+                first_by_graph
+                    .into_iter()
+                    .min_by(
+                        |&((job_a, graph_a), index_a), &((job_b, graph_b), index_b)| {
+                            let load_a = ctx.graph_load(job_a, graph_a);
+                            let load_b = ctx.graph_load(job_b, graph_b);
+                            share(job_a, graph_a, load_a.running)
+                                .total_cmp(&share(job_b, graph_b, load_b.running))
+                                .then_with(|| {
+                                    share(job_a, graph_a, load_a.running + load_a.completed)
+                                        .total_cmp(&share(
+                                            job_b,
+                                            graph_b,
+                                            load_b.running + load_b.completed,
+                                        ))
+                                })
+                                .then(index_a.cmp(&index_b))
+                        },
+                    )
+                    .map(|(_, index)| index)
+            }
+            FairShareMode::RoundRobin { last_graph } => {
+                let after_last = |key: &(&str, &str)| {
+                    last_graph.as_ref().is_none_or(|(job_id, graph_id)| {
+                        *key > (job_id.as_str(), graph_id.as_str())
+                    })
+                };
+                let key = first_by_graph
+                    .keys()
+                    .copied()
+                    .filter(after_last)
+                    .min()
+                    .or_else(|| first_by_graph.keys().copied().min())?;
+
+                *last_graph = Some((key.0.to_string(), key.1.to_string()));
+                first_by_graph.get(&key).copied()
+            }
+        }
+    }
+}
+
+/// Ordena as tasks pelo tempo esperado, estimado a partir das execuções já
+/// concluídas do mesmo graph e config.
+///
+/// Tasks sem nenhuma estimativa vão primeiro nos dois modos, para que o
+/// histórico seja construído o quanto antes. Empates seguem a ordem FIFO.
+#[derive(Debug, Clone, Copy)]
+pub struct ExpectedRuntime {
+    longest_first: bool,
+}
+
+impl ExpectedRuntime {
+    #[must_use]
+    pub const fn shortest_first() -> Self {
+        Self {
+            longest_first: false,
+        }
+    }
+
+    /// Começar pelas tasks mais longas reduz o makespan no fim de um job.
+    #[must_use]
+    pub const fn longest_first() -> Self {
+        Self {
+            longest_first: true,
+        }
+    }
+}
+
+impl SchedulingPolicy for ExpectedRuntime {
+    fn select(&mut self, ctx: &SchedulingContext<'_>) -> Option<usize> {
+        let mut best: Option<(usize, f64)> = None;
+
+        for (index, task) in ctx.candidates() {
+            let Some(estimate) = ctx.estimated_runtime_ms(task) else {
+                return Some(index);
+            };
+            let better = best.is_none_or(|(_, best_estimate)| {
+                if self.longest_first {
+                    estimate > best_estimate
+                } else {
+                    estimate < best_estimate
+                }
+            });
+            if better {
+                best = Some((index, estimate));
+            }
+        }
+
+        best.map(|(index, _)| index)
+    }
+}
+
+/// Prefere entregar ao worker mais execuções dos graphs que ele processou
+/// recentemente, evitando recarregar e pré-processar instâncias grandes.
+///
+/// Quanto mais recente o graph no histórico do worker, maior a preferência.
+/// Quando nenhum deles tem tasks candidatas, a escolha fica com `fallback`.
+pub struct GraphAffinity {
+    fallback: Box<dyn SchedulingPolicy>,
+}
+
+impl GraphAffinity {
+    pub fn new(fallback: impl SchedulingPolicy) -> Self {
+        Self {
+            fallback: Box::new(fallback),
+        }
+    }
+}
+
+impl Default for GraphAffinity {
+    fn default() -> Self {
+        Self::new(Fifo)
+    }
+}
+
+impl SchedulingPolicy for GraphAffinity {
+    fn select(&mut self, ctx: &SchedulingContext<'_>) -> Option<usize> {
+        let recent_graphs = &ctx.worker().recent_graphs;
+        if recent_graphs.is_empty() {
+            return self.fallback.select(ctx);
+        }
+
+        // 0 é o graph mais recente.
+        let mut recency: HashMap<&str, usize> = HashMap::with_capacity(recent_graphs.len());
+        for (rank, graph_id) in recent_graphs.iter().rev().enumerate() {
+            recency.entry(graph_id.as_str()).or_insert(rank);
+        }
+
+        let mut best: Option<(usize, usize)> = None;
+        for (index, task) in ctx.candidates() {
+            let Some(&rank) = recency.get(task.graph_id.as_str()) else {
+                continue;
+            };
+            if best.is_none_or(|(_, best_rank)| rank < best_rank) {
+                best = Some((index, rank));
+                if rank == 0 {
+                    break;
+                }
+            }
+        }
+
+        match best {
+            Some((index, _)) => Some(index),
+            None => self.fallback.select(ctx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::TaskRequirements;
+
+    fn task(job_id: &str, graph_id: &str, run_number: u32) -> Task {
+        let mut task = Task::new(graph_id.to_string(), run_number, "{}".to_string());
+        task.job_id = job_id.to_string();
+        task
+    }
+
+    /// Estado do `TaskManager` visto pelas políticas, montado à mão.
+    struct Fixture {
+        pending: VecDeque<Task>,
+        worker: WorkerInfo,
+        priorities: Priorities,
+        graph_loads: PerGraph<GraphLoad>,
+        runtimes: RuntimeEstimates,
+        paused: PausedWork,
+    }
+
+    impl Fixture {
+        fn new(tasks: Vec<Task>) -> Self {
+            Self {
+                pending: tasks.into(),
+                worker: WorkerInfo::new(Uuid::new_v4()),
+                priorities: Priorities::default(),
+                graph_loads: PerGraph::default(),
+                runtimes: RuntimeEstimates::default(),
+                paused: PausedWork::default(),
+            }
+        }
+
+        fn select(&self, policy: &mut dyn SchedulingPolicy) -> Option<usize> {
+            let ctx = SchedulingContext::new(
+                &self.pending,
+                &self.worker,
+                &self.priorities,
+                &self.graph_loads,
+                &self.runtimes,
+                &self.paused,
+            );
+            policy.select(&ctx)
+        }
+    }
+
+    #[test]
+    fn fifo_and_lifo_pick_the_ends_of_the_queue() {
+        let fixture = Fixture::new(vec![
+            task("j", "a", 0),
+            task("j", "b", 0),
+            task("j", "c", 0),
+        ]);
+        assert_eq!(fixture.select(&mut Fifo), Some(0));
+        assert_eq!(fixture.select(&mut Lifo), Some(2));
+        assert_eq!(Fixture::new(Vec::new()).select(&mut Fifo), None);
+    }
+
+    #[test]
+    fn policies_skip_paused_tasks() {
+        let mut fixture = Fixture::new(vec![
+            task("j", "a", 0),
+            task("j", "b", 0),
+            task("j", "a", 1),
+        ]);
+        fixture.paused.pause_graph("j", "a");
+        assert_eq!(fixture.select(&mut Fifo), Some(1));
+        assert_eq!(fixture.select(&mut Lifo), Some(1));
+        for _ in 0..20 {
+            assert_eq!(fixture.select(&mut Random), Some(1));
+        }
+
+        fixture.paused.pause_job("j");
+        assert_eq!(fixture.select(&mut Fifo), None);
+    }
+
+    #[test]
+    fn random_reaches_every_candidate() {
+        let fixture = Fixture::new(vec![
+            task("j", "a", 0),
+            task("j", "b", 0),
+            task("j", "c", 0),
+        ]);
+        let chosen: HashSet<usize> = (0..200)
+            .filter_map(|_| fixture.select(&mut Random))
+            .collect();
+        assert_eq!(chosen, HashSet::from([0, 1, 2]));
+    }
+
+    #[test]
+    fn higher_effective_priority_goes_first() {
+        let mut urgent = task("j", "a", 1);
+        urgent.priority = 5;
+        let mut fixture = Fixture::new(vec![task("j", "a", 0), urgent, task("j", "b", 0)]);
+        fixture.priorities.mark_in_use();
+        assert_eq!(fixture.select(&mut Fifo), Some(1));
+        assert_eq!(fixture.select(&mut Lifo), Some(1));
+
+        // Prioridade do graph soma com a da task.
+        fixture.priorities.set_graph_priority("j", "b", 10);
+        assert_eq!(fixture.select(&mut Fifo), Some(2));
+        // Só vale para o graph do job configurado.
+        fixture.pending.push_back(task("outro", "b", 0));
+        assert_eq!(fixture.select(&mut Lifo), Some(2));
+    }
+
+    #[test]
+    fn aging_raises_priority_over_time() {
+        let waiting = task("j", "a", 0);
+        let mut priorities = Priorities::default();
+        priorities.set_aging(Some(Duration::from_millis(10)), false);
+        priorities.enqueued(waiting.id);
+
+        let now = Instant::now();
+        assert_eq!(priorities.effective_priority(&waiting, now), 0);
+        let later = now + Duration::from_millis(35);
+        assert_eq!(priorities.effective_priority(&waiting, later), 3);
+
+        priorities.dequeued(waiting.id);
+        assert_eq!(priorities.effective_priority(&waiting, later), 0);
+    }
+
+    #[test]
+    fn priorities_are_skipped_when_nothing_is_configured() {
+        let mut priorities = Priorities::default();
+        assert!(!priorities.in_use());
+        priorities.set_aging(Some(Duration::from_secs(1)), false);
+        assert!(priorities.in_use());
+        priorities.set_aging(None, false);
+        assert!(!priorities.in_use());
+        priorities.set_aging(None, true);
+        assert!(priorities.in_use());
+
+        priorities.set_graph_priority("j", "a", 0);
+        priorities.set_aging(None, false);
+        assert!(!priorities.in_use());
+        priorities.set_graph_priority("j", "a", 1);
+        priorities.set_aging(None, false);
+        assert!(priorities.in_use());
+    }
+
+    #[test]
+    fn fair_share_prefers_the_least_loaded_graph() {
+        let mut fixture = Fixture::new(vec![task("j", "a", 0), task("j", "b", 0)]);
+        fixture.graph_loads.entry("j", "a").running = 2;
+        fixture.graph_loads.entry("j", "b").running = 1;
+        assert_eq!(fixture.select(&mut FairShare::new()), Some(1));
+
+        // Empate em execução: desempata pelas concluídas.
+        fixture.graph_loads.entry("j", "b").running = 2;
+        fixture.graph_loads.entry("j", "b").completed = 5;
+        assert_eq!(fixture.select(&mut FairShare::new()), Some(0));
+    }
+
+    #[test]
+    fn fair_share_weights_are_scoped_by_job() {
+        let mut fixture = Fixture::new(vec![task("j1", "g", 0), task("j2", "g", 0)]);
+        fixture.graph_loads.entry("j1", "g").running = 3;
+        fixture.graph_loads.entry("j2", "g").running = 2;
+        assert_eq!(fixture.select(&mut FairShare::new()), Some(1));
+
+        // Peso 2 no graph "g" do j1 não afeta o graph homônimo do j2.
+        let weights = HashMap::from([(("j1".to_string(), "g".to_string()), 2.0)]);
+        let mut policy = FairShare::weighted(weights);
+        assert_eq!(fixture.select(&mut policy), Some(0));
+
+        let weights = HashMap::from([(("j1".to_string(), "g".to_string()), 0.0)]);
+        let mut policy = FairShare::weighted(weights);
+        fixture.graph_loads.entry("j2", "g").running = 100;
+        assert_eq!(fixture.select(&mut policy), Some(1));
+    }
+
+    #[test]
+    fn fair_share_round_robin_alternates_graphs() {
+        let fixture = Fixture::new(vec![
+            task("j", "b", 0),
+            task("j", "a", 0),
+            task("j", "b", 1),
+            task("j", "c", 0),
+        ]);
+        let mut policy = FairShare::round_robin();
+        let picks: Vec<usize> = (0..4).filter_map(|_| fixture.select(&mut policy)).collect();
+        assert_eq!(picks, vec![1, 0, 3, 1]);
+    }
+
+    #[test]
+    fn tasks_the_worker_cannot_run_are_not_candidates() {
+        let mut gpu = task("j", "a", 0);
+        gpu.requirements = TaskRequirements::default().with_tag("gpu");
+        let mut fixture = Fixture::new(vec![gpu, task("j", "b", 0)]);
+        assert_eq!(fixture.select(&mut Fifo), Some(1));
+
+        // Com prioridades em uso a filtragem também vale.
+        fixture.pending[0].priority = 10;
+        fixture.priorities.mark_in_use();
+        assert_eq!(fixture.select(&mut Fifo), Some(1));
+
+        fixture.worker.capabilities = WorkerCapabilities::default().with_tag("gpu");
+        assert_eq!(fixture.select(&mut Fifo), Some(0));
+
+        let excluded = fixture.worker.worker_id;
+        fixture.pending[0].requirements = fixture.pending[0]
+            .requirements
+            .clone()
+            .with_excluded_worker(excluded);
+        assert_eq!(fixture.select(&mut Fifo), Some(1));
+    }
+
+    #[test]
+    fn runtime_estimates_fall_back_to_the_graph_mean() {
+        let mut runtimes = RuntimeEstimates::default();
+        runtimes.record("j", "g", "{}", 100);
+        runtimes.record("j", "g", "{}", 300);
+        runtimes.record("j", "g", "{\"x\": 1}", 800);
+
+        assert_eq!(runtimes.estimate_ms(&task("j", "g", 0)), Some(200.0));
+        let mut other_config = task("j", "g", 0);
+        other_config.ag_config = "{\"x\": 2}".to_string();
+        assert_eq!(runtimes.estimate_ms(&other_config), Some(400.0));
+        assert_eq!(runtimes.estimate_ms(&task("outro", "g", 0)), None);
+    }
+
+    #[test]
+    fn expected_runtime_orders_by_estimate_with_unknown_first() {
+        let mut fixture = Fixture::new(vec![
+            task("j", "medio", 0),
+            task("j", "curto", 0),
+            task("j", "longo", 0),
+        ]);
+        fixture.runtimes.record("j", "medio", "{}", 50);
+        fixture.runtimes.record("j", "curto", "{}", 10);
+        fixture.runtimes.record("j", "longo", "{}", 90);
+        assert_eq!(
+            fixture.select(&mut ExpectedRuntime::shortest_first()),
+            Some(1)
+        );
+        assert_eq!(
+            fixture.select(&mut ExpectedRuntime::longest_first()),
+            Some(2)
+        );
+
+        fixture.pending.push_back(task("j", "novo", 0));
+        assert_eq!(
+            fixture.select(&mut ExpectedRuntime::shortest_first()),
+            Some(3)
+        );
+        assert_eq!(
+            fixture.select(&mut ExpectedRuntime::longest_first()),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn expected_runtime_ties_keep_fifo_order() {
+        let mut fixture = Fixture::new(vec![task("j", "a", 0), task("j", "a", 1)]);
+        fixture.runtimes.record("j", "a", "{}", 10);
+        assert_eq!(
+            fixture.select(&mut ExpectedRuntime::shortest_first()),
+            Some(0)
+        );
+        assert_eq!(
+            fixture.select(&mut ExpectedRuntime::longest_first()),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn graph_affinity_prefers_the_most_recent_graph() {
+        let mut fixture = Fixture::new(vec![
+            task("j", "a", 0),
+            task("j", "b", 0),
+            task("j", "c", 0),
+        ]);
+        fixture.worker.record_assignment(&task("j", "b", 1));
+        fixture.worker.record_assignment(&task("j", "c", 1));
+        assert_eq!(fixture.worker.last_graph(), Some("c"));
+        assert_eq!(fixture.select(&mut GraphAffinity::default()), Some(2));
+
+        fixture.pending.pop_back();
+        assert_eq!(fixture.select(&mut GraphAffinity::default()), Some(1));
+    }
+
+    #[test]
+    fn graph_affinity_falls_back_without_recent_candidates() {
+        let mut fixture = Fixture::new(vec![task("j", "a", 0), task("j", "b", 0)]);
+        assert_eq!(fixture.select(&mut GraphAffinity::new(Lifo)), Some(1));
+
+        fixture.worker.record_assignment(&task("j", "z", 0));
+        assert_eq!(fixture.select(&mut GraphAffinity::new(Lifo)), Some(1));
+        assert_eq!(fixture.select(&mut GraphAffinity::default()), Some(0));
+    }
+
+    #[test]
+    fn worker_history_is_bounded() {
+        let mut worker = WorkerInfo::new(Uuid::new_v4());
+        for run in 0..(WORKER_HISTORY_LEN as u32 + 5) {
+            worker.record_assignment(&task("j", &run.to_string(), 0));
+        }
+        assert_eq!(worker.recent_graphs.len(), WORKER_HISTORY_LEN);
+        assert_eq!(worker.recent_graphs.front().map(String::as_str), Some("5"));
+        assert_eq!(worker.tasks_assigned, WORKER_HISTORY_LEN as u64 + 5);
+    }
+}
@@ -1,32 +1,96 @@
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use std::error::Error;
+use std::io;
 use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::net::{TcpListener, TcpStream};
+use std::time::Duration;
+use tokio::net::TcpListener;
 use tokio::sync::Mutex;
+use tokio::time::{Instant, sleep, timeout_at};
+use uuid::Uuid;
 
+use crate::common::ClusterSecret;
+use crate::common::ProgressSample;
 use crate::common::Request;
 use crate::common::Response;
+use crate::common::Task;
+use crate::common::TaskResult;
+use crate::common::WorkerCapabilities;
 use crate::host::result_aggregator::ResultAggregator;
 use crate::host::task_manager::TaskManager;
+use crate::transport::{Connection, Listener};
+
+/// Pausa após uma falha de `accept` (ex.: limite de descritores), para não
+/// girar em falso enquanto o erro persiste.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
 
 pub async fn start_server(
     addr: &str,
     task_manager: Arc<Mutex<TaskManager>>,
     result_aggregator: Arc<Mutex<ResultAggregator>>,
+    secret: Option<ClusterSecret>,
 ) -> Result<(), Box<dyn Error>> {
     let listener = TcpListener::bind(addr).await?;
     info!("Host escutando em {addr}");
 
+    serve(listener, task_manager, result_aggregator, secret).await
+}
+
+#[cfg(feature = "websocket")]
+pub async fn start_server_with_websocket(
+    tcp_addr: &str,
+    websocket_addr: &str,
+    task_manager: Arc<Mutex<TaskManager>>,
+    result_aggregator: Arc<Mutex<ResultAggregator>>,
+    secret: Option<ClusterSecret>,
+) -> Result<(), Box<dyn Error>> {
+    use crate::transport::{EitherListener, WebSocketListener};
+
+    let tcp_listener = TcpListener::bind(tcp_addr).await?;
+    let websocket_listener = WebSocketListener::bind(websocket_addr).await?;
+    info!("Host escutando em {tcp_addr} (TCP) e {websocket_addr} (WebSocket)");
+
+    serve(
+        EitherListener::new(tcp_listener, websocket_listener),
+        task_manager,
+        result_aggregator,
+        secret,
+    )
+    .await
+}
+
+pub async fn serve<L: Listener>(
+    mut listener: L,
+    task_manager: Arc<Mutex<TaskManager>>,
+    result_aggregator: Arc<Mutex<ResultAggregator>>,
+    secret: Option<ClusterSecret>,
+) -> Result<(), Box<dyn Error>> {
+    let secret = secret.map(Arc::new);
+
     loop {
-        let (socket, remote_addr) = listener.accept().await?;
+        let (connection, remote_addr) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            // Os listeners sinalizam assim que não aceitarão mais conexões.
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => return Err(e.into()),
+            Err(e) => {
+                warn!("Falha ao aceitar conexão: {e}");
+                sleep(ACCEPT_RETRY_DELAY).await;
+                continue;
+            }
+        };
         info!("Worker {remote_addr}, se conectando");
 
         let task_manager_clone = Arc::clone(&task_manager);
         let result_aggregator_clone = Arc::clone(&result_aggregator);
+        let secret_clone = secret.clone();
 
         tokio::spawn(async move {
-            if let Err(e) = handle_client(socket, task_manager_clone, result_aggregator_clone).await
+            if let Err(e) = handle_client(
+                connection,
+                task_manager_clone,
+                result_aggregator_clone,
+                secret_clone,
+            )
+            .await
             {
                 error!("Error {remote_addr}: {e}");
             }
@@ -34,65 +98,589 @@ pub async fn start_server(
     }
 }
 
-async fn handle_client(
-    socket: TcpStream,
+async fn handle_client<C: Connection>(
+    mut connection: C,
     task_manager: Arc<Mutex<TaskManager>>,
     result_aggregator: Arc<Mutex<ResultAggregator>>,
+    secret: Option<Arc<ClusterSecret>>,
 ) -> Result<(), Box<dyn Error>> {
-    let mut reader = BufReader::new(socket);
-    let mut line_buffer = String::new();
+    let mut session = Session::new(secret);
 
     loop {
-        line_buffer.clear(); // Limpa o buffer para a próxima linha
-
-        // Lê até o delimitador de newline ('\n')
-        let bytes_read = reader.read_line(&mut line_buffer).await?;
-
-        if bytes_read == 0 {
+        let Some(msg) = connection.recv::<Request>().await? else {
             info!("Cliente desconectado.");
             return Ok(());
-        }
-
-        // Tenta desserializar a Request da linha lida
-        let msg: Request = serde_json::from_str(&line_buffer)?;
+        };
         debug!(r"Recebida solicitação do trabalhador: {msg:?}");
 
         let response = match msg {
-            Request::RequestTask { worker_id } => {
-                let mut tm = task_manager.lock().await;
-                if let Some(task) = tm.get_next_task(worker_id) {
+            Request::Hello {
+                worker_id,
+                capabilities,
+            } => session.hello(worker_id, capabilities),
+            Request::Authenticate {
+                worker_id,
+                signature,
+            } => session.authenticate(worker_id, &signature),
+            Request::RequestTask { worker_id, .. }
+            | Request::RequestTasks { worker_id, .. }
+            | Request::ReportResult { worker_id, .. }
+            | Request::ReportResults { worker_id, .. }
+            | Request::ReportProgress { worker_id, .. }
+            | Request::ReportFailure { worker_id, .. }
+            | Request::Heartbeat { worker_id }
+                if !session.is_authorized(worker_id) =>
+            {
+                warn!("Requisição não autenticada do trabalhador {worker_id} rejeitada");
+                Response::Unauthorized {
+                    reason: "Conexão não autenticada".to_string(),
+                }
+            }
+            Request::RequestTask { worker_id, wait_ms } => {
+                let wait = wait_ms.map(Duration::from_millis);
+                if let Some(task) = wait_for_tasks(&task_manager, worker_id, 1, wait)
+                    .await
+                    .pop()
+                {
                     info!(
                         "Atribuindo tarefa {} para o trabalhador {}",
                         task.id, worker_id
                     );
-                    Response::AssignTask { task }
+                    Response::AssignTask {
+                        task: Box::new(task),
+                    }
                 } else {
                     debug!("Nenhuma tarefa disponível para o trabalhador {worker_id}");
                     Response::NoTaskAvailable
                 }
             }
+            Request::RequestTasks {
+                worker_id,
+                max_tasks,
+                wait_ms,
+            } => {
+                let wait = wait_ms.map(Duration::from_millis);
+                let tasks =
+                    wait_for_tasks(&task_manager, worker_id, max_tasks as usize, wait).await;
+                if tasks.is_empty() {
+                    debug!("Nenhuma tarefa disponível para o trabalhador {worker_id}");
+                    Response::NoTaskAvailable
+                } else {
+                    info!(
+                        "Atribuindo lote de {} tarefas para o trabalhador {}",
+                        tasks.len(),
+                        worker_id
+                    );
+                    Response::AssignTasks { tasks }
+                }
+            }
             Request::ReportResult { worker_id, result } => {
-                info!(
-                    "Recebido resultado para a tarefa {} do trabalhador {}",
-                    result.task_id, worker_id
-                );
+                record_results(&task_manager, &result_aggregator, worker_id, vec![result]).await?;
+                Response::Ack
+            }
+            Request::ReportResults { worker_id, results } => {
+                record_results(&task_manager, &result_aggregator, worker_id, results).await?;
+                Response::Ack
+            }
+            Request::ReportProgress {
+                worker_id,
+                task_id,
+                samples,
+            } => {
+                record_progress(
+                    &task_manager,
+                    &result_aggregator,
+                    worker_id,
+                    task_id,
+                    samples,
+                )
+                .await;
+                Response::Ack
+            }
+            Request::ReportFailure {
+                worker_id,
+                task_id,
+                reason,
+            } => {
                 let mut tm = task_manager.lock().await;
-                tm.mark_task_completed(result.task_id)?;
-
-                let mut ra = result_aggregator.lock().await;
-                ra.add_result(result)?;
+                if let Err(e) = tm.fail_task(task_id, worker_id, &reason) {
+                    warn!("Falha da tarefa {task_id} descartada: {e}");
+                }
+                result_aggregator.lock().await.discard_stale_progress(&tm);
                 Response::Ack
             }
             Request::Heartbeat { worker_id } => {
                 debug!("Recebido heartbeat do trabalhador {worker_id}");
+                task_manager.lock().await.renew_leases(worker_id);
                 Response::Ack
             }
         };
 
-        let encoded: Vec<u8> = serde_json::to_vec(&response)?;
-        reader.write_all(&encoded).await?;
-        reader.write_all(b"\n").await?; // Adiciona delimitador de newline
-        reader.flush().await?;
+        if let Some((worker_id, capabilities)) = session.take_registration() {
+            task_manager
+                .lock()
+                .await
+                .register_worker(worker_id, capabilities);
+        }
+
+        connection.send(&response).await?;
         debug!("Resposta enviada para o trabalhador: {response:?}");
     }
 }
+
+async fn wait_for_tasks(
+    task_manager: &Mutex<TaskManager>,
+    worker_id: Uuid,
+    max_tasks: usize,
+    wait: Option<Duration>,
+) -> Vec<Task> {
+    let deadline = wait.map(|wait| Instant::now() + wait);
+    let task_available = task_manager.lock().await.task_notifier();
+
+    loop {
+        // Registra o interesse antes de consultar a fila para não perder uma
+        // notificação que chegue entre a consulta e a espera.
+        let notified = task_available.notified();
+        tokio::pin!(notified);
+        notified.as_mut().enable();
+
+        let tasks = task_manager
+            .lock()
+            .await
+            .get_next_tasks(worker_id, max_tasks);
+        if !tasks.is_empty() {
+            return tasks;
+        }
+
+        let Some(deadline) = deadline else {
+            return tasks;
+        };
+        if timeout_at(deadline, notified).await.is_err() {
+            return tasks;
+        }
+    }
+}
+
+async fn record_results(
+    task_manager: &Mutex<TaskManager>,
+    result_aggregator: &Mutex<ResultAggregator>,
+    worker_id: Uuid,
+    results: Vec<TaskResult>,
+) -> Result<(), Box<dyn Error>> {
+    let mut tm = task_manager.lock().await;
+    let mut ra = result_aggregator.lock().await;
+
+    for result in results {
+        info!(
+            "Recebido resultado para a tarefa {} do trabalhador {}",
+            result.task_id, worker_id
+        );
+        if result.worker_id != worker_id {
+            warn!(
+                "Trabalhador {worker_id} reportou a tarefa {} em nome de {}, descartando",
+                result.task_id, result.worker_id
+            );
+            continue;
+        }
+
+        // Com leases, um resultado pode chegar depois que a task já foi
+        // reatribuída e concluída por outro worker; nesse caso é descartado.
+        let task = match tm.complete_task(&result) {
+            Ok(task) => task,
+            Err(e) => {
+                warn!("Resultado do trabalhador {worker_id} descartado: {e}");
+                continue;
+            }
+        };
+        // Reexecuções de verificação já foram conferidas pelo TaskManager.
+        if task.verifies.is_some() {
+            continue;
+        }
+        ra.add_task_result(&task, result)?;
+        tm.update_adaptive_runs(&task, &ra);
+    }
+    // Tasks reatribuídas ou abortadas desde o último relatório deixam curvas
+    // que nunca serão concluídas.
+    ra.discard_stale_progress(&tm);
+
+    Ok(())
+}
+
+async fn record_progress(
+    task_manager: &Mutex<TaskManager>,
+    result_aggregator: &Mutex<ResultAggregator>,
+    worker_id: Uuid,
+    task_id: Uuid,
+    samples: Vec<ProgressSample>,
+) {
+    let mut tm = task_manager.lock().await;
+    // Progresso também mostra que o worker está vivo.
+    tm.renew_leases(worker_id);
+
+    // Só aceita progresso de quem ainda detém a task; reexecuções de
+    // verificação não têm curva própria.
+    let Some(task) = tm.get_assigned_task(task_id, worker_id) else {
+        debug!("Progresso da tarefa {task_id} do trabalhador {worker_id} descartado");
+        return;
+    };
+    if task.verifies.is_some() {
+        return;
+    }
+    result_aggregator
+        .lock()
+        .await
+        .record_progress(&task.job_id, task_id, worker_id, samples);
+}
+
+struct Session {
+    secret: Option<Arc<ClusterSecret>>,
+    challenge: Option<(Uuid, Vec<u8>)>,
+    authenticated: Option<Uuid>,
+    capabilities: WorkerCapabilities,
+    registration: Option<(Uuid, WorkerCapabilities)>,
+}
+
+impl Session {
+    fn new(secret: Option<Arc<ClusterSecret>>) -> Self {
+        Self {
+            secret,
+            challenge: None,
+            authenticated: None,
+            capabilities: WorkerCapabilities::default(),
+            registration: None,
+        }
+    }
+
+    /// Capacidades do worker aceito nesta conexão, a serem registradas no
+    /// `TaskManager` apenas após a autenticação.
+    fn take_registration(&mut self) -> Option<(Uuid, WorkerCapabilities)> {
+        self.registration.take()
+    }
+
+    fn is_authorized(&self, worker_id: Uuid) -> bool {
+        self.secret.is_none() || self.authenticated == Some(worker_id)
+    }
+
+    fn hello(&mut self, worker_id: Uuid, capabilities: WorkerCapabilities) -> Response {
+        if self.secret.is_none() {
+            debug!("Autenticação desativada, aceitando o trabalhador {worker_id}");
+            self.registration = Some((worker_id, capabilities));
+            return Response::Ack;
+        }
+
+        self.capabilities = capabilities;
+        let nonce = ClusterSecret::generate_nonce();
+        self.challenge = Some((worker_id, nonce.clone()));
+        self.authenticated = None;
+        debug!("Desafio de autenticação enviado para o trabalhador {worker_id}");
+        Response::Challenge { nonce }
+    }
+
+    fn authenticate(&mut self, worker_id: Uuid, signature: &[u8]) -> Response {
+        let Some(secret) = &self.secret else {
+            return Response::Ack;
+        };
+
+        match self.challenge.take() {
+            Some((challenged_id, nonce))
+                if challenged_id == worker_id && secret.verify(&nonce, worker_id, signature) =>
+            {
+                info!("Trabalhador {worker_id} autenticado");
+                self.authenticated = Some(worker_id);
+                let capabilities = std::mem::take(&mut self.capabilities);
+                self.registration = Some((worker_id, capabilities));
+                Response::Ack
+            }
+            Some(_) => {
+                warn!("Assinatura inválida do trabalhador {worker_id}");
+                Response::Unauthorized {
+                    reason: "Assinatura inválida".to_string(),
+                }
+            }
+            None => {
+                warn!("Trabalhador {worker_id} tentou autenticar sem desafio pendente");
+                Response::Unauthorized {
+                    reason: "Nenhum desafio pendente".to_string(),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::DEFAULT_JOB_ID;
+    use crate::host::task_manager::DistributionStrategy;
+    use crate::transport::{self, Connector, MemoryConnector, MemoryListener};
+
+    /// Falha as primeiras chamadas de `accept`, como um host sem descritores.
+    struct FlakyListener {
+        inner: MemoryListener,
+        failures: usize,
+    }
+
+    impl Listener for FlakyListener {
+        type Connection = <MemoryListener as Listener>::Connection;
+
+        async fn accept(&mut self) -> io::Result<(Self::Connection, String)> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                return Err(io::Error::other("Too many open files"));
+            }
+            self.inner.accept().await
+        }
+    }
+
+    type MemoryConnection = <MemoryConnector as Connector>::Connection;
+
+    fn start(task_manager: TaskManager) -> (Arc<Mutex<TaskManager>>, MemoryConnector) {
+        let task_manager = Arc::new(Mutex::new(task_manager));
+        let (listener, connector) = transport::channel();
+        let server = serve(
+            listener,
+            Arc::clone(&task_manager),
+            Arc::new(Mutex::new(ResultAggregator::new())),
+            None,
+        );
+        tokio::spawn(async move {
+            let _ = server.await;
+        });
+        (task_manager, connector)
+    }
+
+    async fn request(connection: &mut MemoryConnection, request: &Request) -> Response {
+        connection.send(request).await.unwrap();
+        connection
+            .recv()
+            .await
+            .unwrap()
+            .expect("host encerrou a conexão")
+    }
+
+    async fn connect(connector: &MemoryConnector) -> (MemoryConnection, Uuid) {
+        let mut connection = connector.connect().await.unwrap();
+        let worker_id = Uuid::new_v4();
+        let hello = Request::Hello {
+            worker_id,
+            capabilities: WorkerCapabilities::default(),
+        };
+        assert!(matches!(
+            request(&mut connection, &hello).await,
+            Response::Ack
+        ));
+        (connection, worker_id)
+    }
+
+    #[tokio::test]
+    async fn transient_accept_errors_do_not_stop_the_server() {
+        let (inner, connector) = transport::channel();
+        let server = tokio::spawn(async move {
+            serve(
+                FlakyListener { inner, failures: 3 },
+                Arc::new(Mutex::new(TaskManager::new(DistributionStrategy::Fifo))),
+                Arc::new(Mutex::new(ResultAggregator::new())),
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())
+        });
+
+        let mut connection = connector.connect().await.unwrap();
+        connection
+            .send(&Request::Hello {
+                worker_id: Uuid::new_v4(),
+                capabilities: WorkerCapabilities::default(),
+            })
+            .await
+            .unwrap();
+        assert!(connection.recv::<Response>().await.unwrap().is_some());
+
+        // Sem conectores o listener encerra, e o servidor junto.
+        drop(connection);
+        drop(connector);
+        let stopped = tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .expect("servidor não encerrou");
+        assert!(stopped.unwrap().is_err());
+    }
+
+    #[tokio::test]
+    async fn long_poll_returns_when_a_task_is_added() {
+        let (task_manager, connector) = start(TaskManager::new(DistributionStrategy::Fifo));
+        let (mut connection, worker_id) = connect(&connector).await;
+
+        tokio::spawn(async move {
+            sleep(Duration::from_millis(100)).await;
+            task_manager.lock().await.add_new_graph_tasks("g", 1, "{}");
+        });
+        let started = Instant::now();
+        let response = request(
+            &mut connection,
+            &Request::RequestTask {
+                worker_id,
+                wait_ms: Some(30_000),
+            },
+        )
+        .await;
+        assert!(matches!(response, Response::AssignTask { .. }));
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn long_poll_gives_up_after_the_wait() {
+        let (_, connector) = start(TaskManager::new(DistributionStrategy::Fifo));
+        let (mut connection, worker_id) = connect(&connector).await;
+
+        let started = Instant::now();
+        let response = request(
+            &mut connection,
+            &Request::RequestTask {
+                worker_id,
+                wait_ms: Some(100),
+            },
+        )
+        .await;
+        assert!(matches!(response, Response::NoTaskAvailable));
+        assert!(started.elapsed() >= Duration::from_millis(100));
+
+        // Sem espera, a resposta é imediata.
+        let response = request(
+            &mut connection,
+            &Request::RequestTask {
+                worker_id,
+                wait_ms: None,
+            },
+        )
+        .await;
+        assert!(matches!(response, Response::NoTaskAvailable));
+    }
+
+    #[tokio::test]
+    async fn long_polls_of_other_workers_are_all_woken() {
+        let (task_manager, connector) = start(TaskManager::new(DistributionStrategy::Fifo));
+        let mut waiting = Vec::new();
+        for _ in 0..3 {
+            let (mut connection, worker_id) = connect(&connector).await;
+            waiting.push(tokio::spawn(async move {
+                request(
+                    &mut connection,
+                    &Request::RequestTask {
+                        worker_id,
+                        wait_ms: Some(30_000),
+                    },
+                )
+                .await
+            }));
+        }
+
+        sleep(Duration::from_millis(100)).await;
+        task_manager.lock().await.add_new_graph_tasks("g", 3, "{}");
+        for response in waiting {
+            let response = tokio::time::timeout(Duration::from_secs(10), response)
+                .await
+                .expect("worker não foi acordado")
+                .unwrap();
+            assert!(matches!(response, Response::AssignTask { .. }));
+        }
+    }
+
+    fn completed(task: &Task, worker_id: Uuid) -> TaskResult {
+        TaskResult {
+            task_id: task.id,
+            graph_id: task.graph_id.clone(),
+            worker_id,
+            fitness: 1.0,
+            objectives: Vec::new(),
+            solution_data: Vec::new(),
+            interations_run: 1,
+            processing_time_ms: 1,
+            seed: task.seed,
+        }
+    }
+
+    #[tokio::test]
+    async fn tasks_are_requested_and_reported_in_batches() {
+        let mut task_manager = TaskManager::new(DistributionStrategy::Fifo);
+        task_manager.add_new_graph_tasks("g", 5, "{}");
+        let (task_manager, connector) = start(task_manager);
+        let (mut connection, worker_id) = connect(&connector).await;
+
+        let batch = Request::RequestTasks {
+            worker_id,
+            max_tasks: 3,
+            wait_ms: None,
+        };
+        let mut tasks = Vec::new();
+        for expected in [3, 2] {
+            let Response::AssignTasks { tasks: batch } = request(&mut connection, &batch).await
+            else {
+                panic!("lote de tasks esperado");
+            };
+            assert_eq!(batch.len(), expected);
+            tasks.extend(batch);
+        }
+        assert!(matches!(
+            request(&mut connection, &batch).await,
+            Response::NoTaskAvailable
+        ));
+
+        let results = tasks
+            .iter()
+            .map(|task| completed(task, worker_id))
+            .collect();
+        let report = Request::ReportResults { worker_id, results };
+        assert!(matches!(
+            request(&mut connection, &report).await,
+            Response::Ack
+        ));
+        assert_eq!(
+            task_manager
+                .lock()
+                .await
+                .get_completed_tasks_count(DEFAULT_JOB_ID),
+            5
+        );
+    }
+
+    #[tokio::test]
+    async fn heartbeats_keep_leases_alive() {
+        let mut task_manager = TaskManager::new(DistributionStrategy::Fifo);
+        task_manager.set_lease_timeout(Some(Duration::from_millis(150)));
+        task_manager.add_new_graph_tasks("g", 1, "{}");
+        let (task_manager, connector) = start(task_manager);
+        let (mut connection, worker_id) = connect(&connector).await;
+
+        let Response::AssignTask { task } = request(
+            &mut connection,
+            &Request::RequestTask {
+                worker_id,
+                wait_ms: None,
+            },
+        )
+        .await
+        else {
+            panic!("task esperada");
+        };
+
+        for _ in 0..4 {
+            sleep(Duration::from_millis(75)).await;
+            let heartbeat = Request::Heartbeat { worker_id };
+            assert!(matches!(
+                request(&mut connection, &heartbeat).await,
+                Response::Ack
+            ));
+        }
+        {
+            let mut tm = task_manager.lock().await;
+            assert!(tm.reclaim_expired_leases().is_empty());
+            assert_eq!(tm.get_lease(task.id).unwrap().worker_id, worker_id);
+        }
+
+        sleep(Duration::from_millis(200)).await;
+        assert_eq!(
+            task_manager.lock().await.reclaim_expired_leases(),
+            vec![task.id]
+        );
+    }
+}
@@ -0,0 +1,154 @@
+//! Aproximações numéricas usadas pelos testes estatísticos do host.
+
+/// Quantil da normal padrão (aproximação de Acklam, erro relativo ~1e-9).
+#[must_use]
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const P_LOW: f64 = 0.024_25;
+
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+
+    let tail = |p: f64| {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+
+    if p < P_LOW {
+        tail(p)
+    } else if p > 1.0 - P_LOW {
+        -tail(1.0 - p)
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+/// Quantil da t de Student com `df` graus de liberdade (exato para 1 e 2,
+/// expansão de Cornish-Fisher nos demais).
+#[must_use]
+pub fn student_t_quantile(p: f64, df: f64) -> f64 {
+    if df <= 1.0 {
+        return (std::f64::consts::PI * (p - 0.5)).tan();
+    }
+    if df <= 2.0 {
+        return (2.0 * p - 1.0) / (2.0 * p * (1.0 - p)).sqrt();
+    }
+
+    let z = normal_quantile(p);
+    let z2 = z * z;
+    let g1 = (z2 + 1.0) * z / 4.0;
+    let g2 = ((5.0 * z2 + 16.0) * z2 + 3.0) * z / 96.0;
+    let g3 = (((3.0 * z2 + 19.0) * z2 + 17.0) * z2 - 15.0) * z / 384.0;
+    let g4 = ((((79.0 * z2 + 776.0) * z2 + 1482.0) * z2 - 1920.0) * z2 - 945.0) * z / 92160.0;
+    z + g1 / df + g2 / df.powi(2) + g3 / df.powi(3) + g4 / df.powi(4)
+}
+
+/// Quantil da qui-quadrado (aproximação de Wilson-Hilferty).
+#[must_use]
+pub fn chi_squared_quantile(p: f64, df: f64) -> f64 {
+    let z = normal_quantile(p);
+    let h = 2.0 / (9.0 * df);
+    (df * (1.0 - h + z * h.sqrt()).powi(3)).max(0.0)
+}
+
+#[must_use]
+pub fn mean(samples: &[f64]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    samples.iter().sum::<f64>() / samples.len() as f64
+}
+
+/// Variância amostral (denominador `n - 1`).
+#[must_use]
+pub fn sample_variance(samples: &[f64]) -> f64 {
+    if samples.len() < 2 {
+        return 0.0;
+    }
+    let mean = mean(samples);
+    samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (samples.len() - 1) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "esperado {expected}, obtido {actual}"
+        );
+    }
+
+    #[test]
+    fn normal_quantile_matches_tables() {
+        assert_close(normal_quantile(0.5), 0.0, 1e-12);
+        assert_close(normal_quantile(0.975), 1.959_964, 1e-6);
+        assert_close(normal_quantile(0.01), -2.326_348, 1e-6);
+        assert_eq!(normal_quantile(0.0), f64::NEG_INFINITY);
+        assert_eq!(normal_quantile(1.0), f64::INFINITY);
+    }
+
+    #[test]
+    fn student_t_quantile_matches_tables() {
+        assert_close(student_t_quantile(0.975, 1.0), 12.706, 1e-3);
+        assert_close(student_t_quantile(0.975, 2.0), 4.303, 1e-3);
+        assert_close(student_t_quantile(0.975, 5.0), 2.571, 1e-2);
+        assert_close(student_t_quantile(0.975, 10.0), 2.228, 1e-3);
+        assert_close(student_t_quantile(0.95, 30.0), 1.697, 1e-3);
+        assert_close(student_t_quantile(0.025, 10.0), -2.228, 1e-3);
+    }
+
+    #[test]
+    fn chi_squared_quantile_matches_tables() {
+        // Wilson-Hilferty perde precisão com poucos graus de liberdade.
+        assert_close(chi_squared_quantile(0.95, 2.0), 5.991, 1e-1);
+        assert_close(chi_squared_quantile(0.95, 5.0), 11.070, 5e-2);
+        assert_close(chi_squared_quantile(0.95, 10.0), 18.307, 5e-2);
+    }
+
+    #[test]
+    fn mean_and_sample_variance() {
+        let samples = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        assert_close(mean(&samples), 5.0, 1e-12);
+        assert_close(sample_variance(&samples), 32.0 / 7.0, 1e-12);
+        assert_eq!(mean(&[]), 0.0);
+        assert_eq!(sample_variance(&[3.0]), 0.0);
+    }
+}
@@ -0,0 +1,420 @@
+use std::error::Error;
+
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+use serde_json::{Map, Value};
+
+use crate::common::{ConfigPayload, ParameterAssignment, Task};
+
+/// Valores que um parâmetro pode assumir numa varredura.
+#[derive(Debug, Clone)]
+pub enum ParameterDomain {
+    /// Conjunto discreto de valores.
+    Values(Vec<Value>),
+    /// Intervalo contínuo `[min, max]`.
+    Float { min: f64, max: f64 },
+    /// Intervalo inteiro `[min, max]`.
+    Int { min: i64, max: i64 },
+}
+
+impl ParameterDomain {
+    fn grid_points(&self, steps: usize) -> Vec<Value> {
+        let steps = steps.max(1);
+        match self {
+            Self::Values(values) => values.clone(),
+            Self::Float { min, max } => {
+                if steps == 1 {
+                    return vec![Value::from(*min)];
+                }
+                (0..steps)
+                    .map(|i| Value::from(min + (max - min) * i as f64 / (steps - 1) as f64))
+                    .collect()
+            }
+            Self::Int { min, max } => {
+                // Em i128, já que `max - min` estoura i64 em intervalos largos.
+                let span = (i128::from(*max) - i128::from(*min)) as f64;
+                let mut points: Vec<i64> = (0..steps)
+                    .map(|i| {
+                        if steps == 1 {
+                            *min
+                        } else {
+                            let offset = (span * i as f64 / (steps - 1) as f64).round() as i128;
+                            (i128::from(*min) + offset).min(i128::from(*max)) as i64
+                        }
+                    })
+                    .collect();
+                points.dedup();
+                points.into_iter().map(Value::from).collect()
+            }
+        }
+    }
+
+    /// Valor no quantil `u` (em `[0, 1)`) do domínio.
+    fn at_quantile(&self, u: f64) -> Value {
+        match self {
+            Self::Values(values) => {
+                let index = ((u * values.len() as f64) as usize).min(values.len() - 1);
+                values[index].clone()
+            }
+            Self::Float { min, max } => Value::from(min + (max - min) * u),
+            Self::Int { min, max } => {
+                let span = (i128::from(*max) - i128::from(*min) + 1) as f64;
+                let value = (i128::from(*min) + (u * span) as i128).min(i128::from(*max));
+                Value::from(value as i64)
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            Self::Values(values) => values.is_empty(),
+            Self::Float { min, max } => min > max,
+            Self::Int { min, max } => min > max,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SweepStrategy {
+    /// Produto cartesiano; intervalos são discretizados em `steps` pontos.
+    Grid {
+        steps: usize,
+    },
+    Random {
+        samples: usize,
+        seed: Option<u64>,
+    },
+    LatinHypercube {
+        samples: usize,
+        seed: Option<u64>,
+    },
+}
+
+/// Varredura de parâmetros sobre um `ag_config` JSON base.
+///
+/// Nomes de parâmetros com `.` apontam para campos aninhados, por exemplo
+/// `mutation.rate`.
+#[derive(Debug, Clone)]
+pub struct ParameterSweep {
+    base_config: Map<String, Value>,
+    parameters: Vec<(String, ParameterDomain)>,
+    strategy: SweepStrategy,
+    graphs: Vec<String>,
+    runs_per_config: u32,
+}
+
+impl ParameterSweep {
+    pub fn new(base_config: &str, strategy: SweepStrategy) -> Result<Self, Box<dyn Error>> {
+        let Value::Object(base_config) = serde_json::from_str(base_config)? else {
+            return Err("O ag_config base da varredura precisa ser um objeto JSON.".into());
+        };
+        Ok(Self {
+            base_config,
+            parameters: Vec::new(),
+            strategy,
+            graphs: Vec::new(),
+            runs_per_config: 1,
+        })
+    }
+
+    /// Igual a `new`, a partir da configuração tipada do runner, que precisa
+    /// serializar como um objeto JSON.
+    pub fn from_typed<C: ConfigPayload>(
+        base_config: &C,
+        strategy: SweepStrategy,
+    ) -> Result<Self, Box<dyn Error>> {
+        let base_config = base_config
+            .encode_config()
+            .map_err(|e| e as Box<dyn Error>)?;
+        Self::new(&base_config, strategy)
+    }
+
+    pub fn with_parameter(
+        mut self,
+        name: impl Into<String>,
+        domain: ParameterDomain,
+    ) -> Result<Self, Box<dyn Error>> {
+        let name = name.into();
+        if domain.is_empty() {
+            return Err(format!("Domínio vazio para o parâmetro {name}").into());
+        }
+        self.parameters.retain(|(existing, _)| *existing != name);
+        self.parameters.push((name, domain));
+        Ok(self)
+    }
+
+    #[must_use]
+    pub fn with_graph(mut self, graph_id: impl Into<String>) -> Self {
+        self.graphs.push(graph_id.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_graphs<I, S>(mut self, graph_ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.graphs.extend(graph_ids.into_iter().map(Into::into));
+        self
+    }
+
+    #[must_use]
+    pub const fn with_runs_per_config(mut self, runs_per_config: u32) -> Self {
+        self.runs_per_config = runs_per_config;
+        self
+    }
+
+    #[must_use]
+    pub fn graphs(&self) -> &[String] {
+        &self.graphs
+    }
+
+    /// Expande o espaço de parâmetros conforme a estratégia.
+    #[must_use]
+    pub fn assignments(&self) -> Vec<ParameterAssignment> {
+        match self.strategy {
+            SweepStrategy::Grid { steps } => {
+                let mut assignments = vec![ParameterAssignment::new()];
+                for (name, domain) in &self.parameters {
+                    let points = domain.grid_points(steps);
+                    assignments = assignments
+                        .into_iter()
+                        .flat_map(|assignment| {
+                            points.iter().map(move |point| {
+                                let mut assignment = assignment.clone();
+                                assignment.insert(name.clone(), point.clone());
+                                assignment
+                            })
+                        })
+                        .collect();
+                }
+                assignments
+            }
+            SweepStrategy::Random { samples, seed } => {
+                let mut rng = make_rng(seed);
+                (0..samples)
+                    .map(|_| {
+                        self.parameters
+                            .iter()
+                            .map(|(name, domain)| (name.clone(), domain.at_quantile(rng.random())))
+                            .collect()
+                    })
+                    .collect()
+            }
+            SweepStrategy::LatinHypercube { samples, seed } => {
+                let mut rng = make_rng(seed);
+                let mut assignments = vec![ParameterAssignment::new(); samples];
+                for (name, domain) in &self.parameters {
+                    // Cada estrato de cada dimensão recebe exatamente uma amostra.
+                    let mut strata: Vec<usize> = (0..samples).collect();
+                    strata.shuffle(&mut rng);
+                    for (assignment, stratum) in assignments.iter_mut().zip(strata) {
+                        let u = (stratum as f64 + rng.random::<f64>()) / samples as f64;
+                        assignment.insert(name.clone(), domain.at_quantile(u));
+                    }
+                }
+                assignments
+            }
+        }
+    }
+
+    /// `ag_config` base com os valores de `assignment` aplicados.
+    #[must_use]
+    pub fn config_for(&self, assignment: &ParameterAssignment) -> String {
+        let mut config = self.base_config.clone();
+        for (name, value) in assignment {
+            set_path(&mut config, name, value.clone());
+        }
+        Value::Object(config).to_string()
+    }
+
+    /// Tasks da varredura, geradas sob demanda: para cada configuração, cada
+    /// graph recebe `runs_per_config` execuções.
+    pub fn into_tasks(self) -> impl Iterator<Item = Task> + Send + 'static {
+        let assignments = self.assignments();
+        self.into_tasks_for(assignments)
+    }
+
+    /// Como `into_tasks`, para atribuições já geradas por `assignments`; evita
+    /// sortear de novo quando a amostragem não tem semente.
+    pub fn into_tasks_for(
+        self,
+        assignments: Vec<ParameterAssignment>,
+    ) -> impl Iterator<Item = Task> + Send + 'static {
+        let configs: Vec<(ParameterAssignment, String)> = assignments
+            .into_iter()
+            .map(|assignment| {
+                let config = self.config_for(&assignment);
+                (assignment, config)
+            })
+            .collect();
+        let graphs = self.graphs;
+        let runs_per_config = self.runs_per_config;
+
+        configs.into_iter().flat_map(move |(assignment, config)| {
+            graphs.clone().into_iter().flat_map(move |graph_id| {
+                let assignment = assignment.clone();
+                let config = config.clone();
+                (0..runs_per_config).map(move |run_number| {
+                    let mut task = Task::new(graph_id.clone(), run_number, config.clone());
+                    task.parameters = assignment.clone();
+                    task
+                })
+            })
+        })
+    }
+}
+
+/// Chave canônica de uma atribuição, usada para agrupar resultados.
+#[must_use]
+pub fn configuration_key(parameters: &ParameterAssignment) -> String {
+    serde_json::to_string(parameters).unwrap_or_default()
+}
+
+fn make_rng(seed: Option<u64>) -> StdRng {
+    seed.map_or_else(StdRng::from_os_rng, StdRng::seed_from_u64)
+}
+
+fn set_path(config: &mut Map<String, Value>, path: &str, value: Value) {
+    match path.split_once('.') {
+        Some((head, rest)) => {
+            let entry = config
+                .entry(head)
+                .or_insert_with(|| Value::Object(Map::new()));
+            if !entry.is_object() {
+                *entry = Value::Object(Map::new());
+            }
+            if let Value::Object(inner) = entry {
+                set_path(inner, rest, value);
+            }
+        }
+        None => {
+            config.insert(path.to_string(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sweep(strategy: SweepStrategy) -> ParameterSweep {
+        ParameterSweep::new(r#"{"population": 10, "mutation": {"rate": 0.1}}"#, strategy)
+            .unwrap()
+            .with_parameter(
+                "mutation.rate",
+                ParameterDomain::Float { min: 0.0, max: 1.0 },
+            )
+            .unwrap()
+            .with_parameter("population", ParameterDomain::Int { min: 10, max: 19 })
+            .unwrap()
+            .with_graph("a")
+            .with_graph("b")
+    }
+
+    fn values(assignments: &[ParameterAssignment], name: &str) -> Vec<f64> {
+        assignments
+            .iter()
+            .map(|assignment| assignment[name].as_f64().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn grid_is_the_cartesian_product() {
+        let sweep = sweep(SweepStrategy::Grid { steps: 3 });
+        let assignments = sweep.assignments();
+        assert_eq!(assignments.len(), 9);
+
+        let mut rates = values(&assignments, "mutation.rate");
+        rates.sort_by(f64::total_cmp);
+        rates.dedup();
+        assert_eq!(rates, vec![0.0, 0.5, 1.0]);
+
+        let mut populations = values(&assignments, "population");
+        populations.sort_by(f64::total_cmp);
+        populations.dedup();
+        assert_eq!(populations, vec![10.0, 15.0, 19.0]);
+    }
+
+    #[test]
+    fn tasks_cover_every_config_and_graph() {
+        let tasks: Vec<Task> = sweep(SweepStrategy::Grid { steps: 2 })
+            .into_tasks()
+            .collect();
+        assert_eq!(tasks.len(), 4 * 2);
+        let config: Value = serde_json::from_str(&tasks[0].ag_config).unwrap();
+        assert_eq!(
+            config["mutation"]["rate"],
+            tasks[0].parameters["mutation.rate"]
+        );
+        assert_eq!(config["population"], tasks[0].parameters["population"]);
+    }
+
+    #[test]
+    fn seeded_sampling_is_reproducible() {
+        for strategy in [
+            SweepStrategy::Random {
+                samples: 20,
+                seed: Some(7),
+            },
+            SweepStrategy::LatinHypercube {
+                samples: 20,
+                seed: Some(7),
+            },
+        ] {
+            let sweep = sweep(strategy);
+            assert_eq!(sweep.assignments(), sweep.assignments());
+            assert_eq!(sweep.assignments().len(), 20);
+        }
+    }
+
+    #[test]
+    fn latin_hypercube_puts_one_sample_in_each_stratum() {
+        let samples = 10;
+        let sweep = sweep(SweepStrategy::LatinHypercube {
+            samples,
+            seed: Some(3),
+        });
+        let mut strata: Vec<usize> = values(&sweep.assignments(), "mutation.rate")
+            .into_iter()
+            .map(|rate| (rate * samples as f64) as usize)
+            .collect();
+        strata.sort_unstable();
+        assert_eq!(strata, (0..samples).collect::<Vec<_>>());
+
+        // O domínio inteiro tem exatamente um valor por estrato.
+        let mut populations = values(&sweep.assignments(), "population");
+        populations.sort_by(f64::total_cmp);
+        assert_eq!(populations, (10..20).map(f64::from).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn wide_int_domains_do_not_overflow() {
+        let domain = ParameterDomain::Int {
+            min: i64::MIN,
+            max: i64::MAX,
+        };
+        let points = domain.grid_points(3);
+        assert_eq!(points.first(), Some(&Value::from(i64::MIN)));
+        assert_eq!(points.last(), Some(&Value::from(i64::MAX)));
+        assert_eq!(domain.at_quantile(0.0), Value::from(i64::MIN));
+        assert!(domain.at_quantile(0.999_999).as_i64().is_some());
+    }
+
+    #[test]
+    fn empty_domains_are_rejected() {
+        let sweep = ParameterSweep::new("{}", SweepStrategy::Grid { steps: 2 }).unwrap();
+        assert!(
+            sweep
+                .clone()
+                .with_parameter("x", ParameterDomain::Values(Vec::new()))
+                .is_err()
+        );
+        assert!(
+            sweep
+                .with_parameter("x", ParameterDomain::Int { min: 2, max: 1 })
+                .is_err()
+        );
+        assert!(ParameterSweep::new("[1, 2]", SweepStrategy::Grid { steps: 2 }).is_err());
+    }
+}
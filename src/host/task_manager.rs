@@ -1,13 +1,30 @@
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
     error::Error,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use log::{debug, error, info, warn};
-use rand::seq::IndexedRandom;
+use serde::Serialize;
+use tokio::sync::Notify;
 use uuid::Uuid;
 
-use crate::common::Task;
+use super::adaptive::{AdaptiveGraph, AdaptiveRunsConfig, AdaptiveStop};
+use super::dependencies::{Dependency, Downstream, FollowUpGenerator, Stage};
+use super::racing::{Race, RaceConfig};
+use super::result_aggregator::ResultAggregator;
+use super::scheduling::{
+    Fifo, GraphLoad, Lifo, PausedWork, PerGraph, Priorities, Random, RuntimeEstimates,
+    SchedulingContext, SchedulingPolicy, WorkerInfo,
+};
+use super::sweep::ParameterSweep;
+use super::task_source::{TaskIterator, TaskSource};
+use super::verification::VerificationRecord;
+use crate::common::{
+    ConfigPayload, DEFAULT_JOB_ID, Objective, PayloadError, SolutionPayload, Task,
+    TaskRequirements, TaskResult, WorkerCapabilities, derive_task_seed,
+};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum TaskStatus {
@@ -15,6 +32,9 @@ pub enum TaskStatus {
     Assigned,
     Completed,
     Failed,
+    Aborted,
+    /// Aguardando as dependências terminarem para entrar na fila.
+    Waiting,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -24,96 +44,1902 @@ pub enum DistributionStrategy {
     Random,
 }
 
+impl DistributionStrategy {
+    #[must_use]
+    pub fn into_policy(self) -> Box<dyn SchedulingPolicy> {
+        match self {
+            Self::Fifo => Box::new(Fifo),
+            Self::Lifo => Box::new(Lifo),
+            Self::Random => Box::new(Random),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GraphTaskOptions {
+    /// Job (experimento) ao qual as tasks pertencem.
+    pub job_id: String,
+    /// Prioridade de cada task criada.
+    pub priority: i32,
+    /// Se definida, substitui a prioridade do graph inteiro.
+    pub graph_priority: Option<i32>,
+    /// Só workers que satisfazem esses requisitos recebem as tasks.
+    pub requirements: TaskRequirements,
+    /// Se definida, fixa a direção da otimização do graph no job.
+    pub objective: Option<Objective>,
+}
+
+impl Default for GraphTaskOptions {
+    fn default() -> Self {
+        Self {
+            job_id: DEFAULT_JOB_ID.to_string(),
+            priority: 0,
+            graph_priority: None,
+            requirements: TaskRequirements::default(),
+            objective: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TaskLease {
+    pub worker_id: Uuid,
+    pub assigned_at: Instant,
+    pub expires_at: Option<Instant>,
+}
+
+/// Registro de um abort, incluído no relatório do job.
+#[derive(Debug, Clone, Serialize)]
+pub struct AbortRecord {
+    /// `None` quando o job inteiro foi abortado.
+    pub graph_id: Option<String>,
+    pub aborted_at_unix_ms: u64,
+    pub pending_dropped: usize,
+    pub assigned_cancelled: usize,
+}
+
+/// Quantas tasks de um graph existem no job e quantas já terminaram.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GraphProgress {
+    pub total: usize,
+    pub completed: usize,
+    pub aborted: usize,
+    /// Tasks que esgotaram as tentativas.
+    pub failed: usize,
+}
+
+impl GraphProgress {
+    #[must_use]
+    pub const fn is_finished(&self) -> bool {
+        self.total > 0 && self.completed + self.aborted + self.failed == self.total
+    }
+}
+
+#[derive(Debug)]
+struct Job {
+    /// Semente da qual derivam as sementes de todas as tasks do job.
+    master_seed: u64,
+    objective: Objective,
+    graph_objectives: HashMap<String, Objective>,
+    objective_directions: HashMap<String, Vec<Objective>>,
+    reference_points: HashMap<String, Vec<f64>>,
+    tasks_status: HashMap<Uuid, TaskStatus>,
+    graph_progress: HashMap<String, GraphProgress>,
+    graph_tasks: HashMap<String, HashSet<Uuid>>,
+    aborts: Vec<AbortRecord>,
+    verifications: Vec<VerificationRecord>,
+}
+
+impl Default for Job {
+    fn default() -> Self {
+        Self {
+            master_seed: rand::random(),
+            objective: Objective::default(),
+            graph_objectives: HashMap::new(),
+            objective_directions: HashMap::new(),
+            reference_points: HashMap::new(),
+            tasks_status: HashMap::new(),
+            graph_progress: HashMap::new(),
+            graph_tasks: HashMap::new(),
+            aborts: Vec::new(),
+            verifications: Vec::new(),
+        }
+    }
+}
+
+/// Quantas tasks vindas de fontes preguiçosas ficam materializadas na fila.
+pub const DEFAULT_SOURCE_BUFFER: usize = 1024;
+/// Tasks que nenhum worker pode receber agora não contam para o buffer, mas a
+/// fila nunca passa deste múltiplo dele, para que uma fonte não seja
+/// materializada inteira enquanto seus graphs estão pausados.
+const SOURCE_BUFFER_OVERFLOW: usize = 4;
+/// Quantas vezes uma task que falha volta para a fila antes de ser descartada.
+pub const DEFAULT_MAX_TASK_ATTEMPTS: u32 = 3;
+
 pub struct TaskManager {
     pending_tasks: VecDeque<Task>,
-    assigned_tasks: HashMap<Uuid, (Task, Uuid)>, // TaskId -> (Task, WorkerId)
-    all_tasks_status: HashMap<Uuid, TaskStatus>,
-    distribution_strategy: DistributionStrategy,
+    assigned_tasks: HashMap<Uuid, (Task, TaskLease)>,
+    jobs: HashMap<String, Job>,
+    workers: HashMap<Uuid, WorkerInfo>,
+    scheduling_policy: Box<dyn SchedulingPolicy>,
+    priorities: Priorities,
+    graph_loads: PerGraph<GraphLoad>,
+    runtimes: RuntimeEstimates,
+    paused: PausedWork,
+    stages: Vec<Stage>,
+    sources: VecDeque<TaskSource>,
+    source_buffer: usize,
+    races: Vec<Race>,
+    adaptive_graphs: Vec<AdaptiveGraph>,
+    verification_rate: f64,
+    /// Resultado original de cada reexecução de verificação em andamento, com o
+    /// worker que detinha o lease da execução original.
+    pending_verifications: HashMap<Uuid, (Uuid, TaskResult)>,
+    lease_timeout: Option<Duration>,
+    max_task_attempts: u32,
+    /// Falhas de cada task que ainda pode ser reexecutada.
+    failed_attempts: HashMap<Uuid, u32>,
+    task_available: Arc<Notify>,
 }
 
 impl TaskManager {
     pub fn new(distribution_strategy: DistributionStrategy) -> Self {
+        Self::with_policy(distribution_strategy.into_policy())
+    }
+
+    pub fn with_policy(scheduling_policy: Box<dyn SchedulingPolicy>) -> Self {
         Self {
             pending_tasks: VecDeque::new(),
             assigned_tasks: HashMap::new(),
-            all_tasks_status: HashMap::new(),
-            distribution_strategy,
+            jobs: HashMap::new(),
+            workers: HashMap::new(),
+            scheduling_policy,
+            priorities: Priorities::default(),
+            graph_loads: PerGraph::default(),
+            runtimes: RuntimeEstimates::default(),
+            paused: PausedWork::default(),
+            stages: Vec::new(),
+            sources: VecDeque::new(),
+            source_buffer: DEFAULT_SOURCE_BUFFER,
+            races: Vec::new(),
+            adaptive_graphs: Vec::new(),
+            verification_rate: 0.0,
+            pending_verifications: HashMap::new(),
+            lease_timeout: None,
+            max_task_attempts: DEFAULT_MAX_TASK_ATTEMPTS,
+            failed_attempts: HashMap::new(),
+            task_available: Arc::new(Notify::new()),
         }
     }
 
     pub fn add_new_graph_tasks(&mut self, graph_id: &str, num_runs: u32, ag_config: &str) {
-        info!("Adicionando {num_runs} tasks para o graph {graph_id}");
+        self.add_new_graph_tasks_with(graph_id, num_runs, ag_config, &GraphTaskOptions::default());
+    }
+
+    pub fn add_new_graph_tasks_with(
+        &mut self,
+        graph_id: &str,
+        num_runs: u32,
+        ag_config: &str,
+        options: &GraphTaskOptions,
+    ) {
+        info!(
+            "Adicionando {num_runs} tasks para o graph {graph_id} no job {}",
+            options.job_id
+        );
+        self.create_job(&options.job_id);
+        self.apply_graph_options(graph_id, options);
+
         for i in 0..num_runs {
-            let task = Task::new(graph_id.to_string(), i, ag_config.to_string());
-            self.pending_tasks.push_back(task.clone());
-            self.all_tasks_status.insert(task.id, TaskStatus::Pending);
+            let mut task = Task::new(graph_id.to_string(), i, ag_config.to_string());
+            task.job_id.clone_from(&options.job_id);
+            task.priority = options.priority;
+            task.requirements = options.requirements.clone();
+            self.enqueue(task);
         }
         info!("Tasks pendentes: {}", self.pending_tasks.len());
+        self.task_available.notify_waiters();
     }
 
-    pub fn get_next_task(&mut self, worker_id: Uuid) -> Option<Task> {
-        let task = match self.distribution_strategy {
-            DistributionStrategy::Fifo => self.pending_tasks.pop_front(),
-            DistributionStrategy::Lifo => self.pending_tasks.pop_back(),
-            DistributionStrategy::Random => {
-                if self.pending_tasks.is_empty() {
-                    None
-                } else {
-                    let mut rng = rand::rng();
-                    let index = *(0..self.pending_tasks.len())
-                        .collect::<Vec<usize>>()
-                        .choose(&mut rng)
-                        .unwrap();
-                    self.pending_tasks.remove(index)
+    /// Igual a `add_new_graph_tasks_with`, com a configuração já tipada; o
+    /// runner dos workers deve usar o mesmo tipo.
+    pub fn add_typed_graph_tasks<C: ConfigPayload>(
+        &mut self,
+        graph_id: &str,
+        num_runs: u32,
+        ag_config: &C,
+        options: &GraphTaskOptions,
+    ) -> Result<(), PayloadError> {
+        let ag_config = ag_config.encode_config()?;
+        self.add_new_graph_tasks_with(graph_id, num_runs, &ag_config, options);
+        Ok(())
+    }
+
+    /// Igual a `add_new_graph_tasks_with`, mas as tasks só são criadas
+    /// conforme a fila esvazia.
+    pub fn add_lazy_graph_tasks(
+        &mut self,
+        graph_id: &str,
+        num_runs: u32,
+        ag_config: &str,
+        options: &GraphTaskOptions,
+    ) {
+        self.create_job(&options.job_id);
+        self.apply_graph_options(graph_id, options);
+
+        let graph_id = graph_id.to_string();
+        let ag_config = ag_config.to_string();
+        let priority = options.priority;
+        let requirements = options.requirements.clone();
+        self.add_task_source(
+            &options.job_id,
+            (0..num_runs).map(move |i| {
+                let mut task = Task::new(graph_id.clone(), i, ag_config.clone());
+                task.priority = priority;
+                task.requirements = requirements.clone();
+                task
+            }),
+        );
+    }
+
+    /// Igual a `add_lazy_graph_tasks`, com a configuração já tipada.
+    pub fn add_typed_lazy_graph_tasks<C: ConfigPayload>(
+        &mut self,
+        graph_id: &str,
+        num_runs: u32,
+        ag_config: &C,
+        options: &GraphTaskOptions,
+    ) -> Result<(), PayloadError> {
+        let ag_config = ag_config.encode_config()?;
+        self.add_lazy_graph_tasks(graph_id, num_runs, &ag_config, options);
+        Ok(())
+    }
+
+    /// Enfileira, sob demanda, as tasks de uma varredura de parâmetros.
+    /// Retorna quantas configurações foram geradas.
+    pub fn add_parameter_sweep(
+        &mut self,
+        sweep: ParameterSweep,
+        options: &GraphTaskOptions,
+    ) -> usize {
+        self.create_job(&options.job_id);
+        for graph_id in sweep.graphs() {
+            self.apply_graph_options(graph_id, options);
+        }
+        let assignments = sweep.assignments();
+        let configurations = assignments.len();
+        info!(
+            "Varredura com {configurations} configurações adicionada ao job {}",
+            options.job_id
+        );
+        let priority = options.priority;
+        let requirements = options.requirements.clone();
+        self.add_task_source(
+            &options.job_id,
+            sweep.into_tasks_for(assignments).map(move |mut task| {
+                task.priority = priority;
+                task.requirements = requirements.clone();
+                task
+            }),
+        );
+        configurations
+    }
+
+    /// Adiciona `min_runs` execuções de um graph; outras são geradas por
+    /// `update_adaptive_runs` até o intervalo de confiança da média da fitness
+    /// ficar estreito o bastante ou `max_runs` ser atingido.
+    pub fn add_adaptive_graph_tasks(
+        &mut self,
+        graph_id: &str,
+        ag_config: &str,
+        config: AdaptiveRunsConfig,
+        options: &GraphTaskOptions,
+    ) {
+        info!(
+            "Adicionando graph adaptativo {graph_id} no job {} com {} a {} execuções",
+            options.job_id, config.min_runs, config.max_runs
+        );
+        self.create_job(&options.job_id);
+        self.apply_graph_options(graph_id, options);
+        self.adaptive_graphs
+            .retain(|adaptive| adaptive.job_id != options.job_id || adaptive.graph_id != graph_id);
+
+        // Continua a numeração das execuções que o graph já tem, para não
+        // repetir sementes.
+        let first_run = self.get_graph_progress(&options.job_id, graph_id).total as u32;
+        let mut adaptive = AdaptiveGraph {
+            job_id: options.job_id.clone(),
+            graph_id: graph_id.to_string(),
+            ag_config: ag_config.to_string(),
+            config,
+            runs_created: 0,
+            interval: None,
+            stopped: None,
+            fitness: Vec::new(),
+            failed: 0,
+            task_ids: HashSet::new(),
+            first_run,
+            priority: options.priority,
+            requirements: options.requirements.clone(),
+        };
+        let tasks: Vec<Task> = (0..config.min_runs).map(|_| adaptive.next_task()).collect();
+        self.adaptive_graphs.push(adaptive);
+        for task in tasks {
+            self.enqueue(task);
+        }
+        self.task_available.notify_waiters();
+    }
+
+    /// Igual a `add_adaptive_graph_tasks`, com a configuração já tipada.
+    pub fn add_typed_adaptive_graph_tasks<C: ConfigPayload>(
+        &mut self,
+        graph_id: &str,
+        ag_config: &C,
+        config: AdaptiveRunsConfig,
+        options: &GraphTaskOptions,
+    ) -> Result<(), PayloadError> {
+        let ag_config = ag_config.encode_config()?;
+        self.add_adaptive_graph_tasks(graph_id, &ag_config, config, options);
+        Ok(())
+    }
+
+    /// Reavalia o graph da task com os resultados já agregados e enfileira
+    /// mais execuções se necessário. O servidor chama após cada resultado.
+    pub fn update_adaptive_runs(&mut self, task: &Task, aggregator: &ResultAggregator) {
+        let Some(adaptive) = self
+            .adaptive_graphs
+            .iter_mut()
+            .find(|adaptive| adaptive.task_ids.contains(&task.id))
+        else {
+            return;
+        };
+        if adaptive.is_finished() {
+            return;
+        }
+
+        let Some(fitness) = aggregator
+            .get_all_results(&task.job_id)
+            .and_then(|results| results.get(&task.graph_id))
+            .and_then(|results| {
+                results
+                    .iter()
+                    .rev()
+                    .find(|result| result.task_id == task.id)
+            })
+            .map(|result| result.fitness)
+        else {
+            return;
+        };
+        let new_runs = adaptive.update(fitness);
+
+        if let Some(stop) = adaptive.stopped {
+            info!(
+                "Graph {} do job {} encerrado após {} execuções: {stop:?}",
+                adaptive.graph_id, adaptive.job_id, adaptive.runs_created
+            );
+            self.release_ready_stages();
+            return;
+        }
+        if new_runs == 0 {
+            return;
+        }
+
+        debug!(
+            "Intervalo do graph {} ainda largo ({:?}), adicionando {new_runs} execuções",
+            adaptive.graph_id, adaptive.interval
+        );
+        let tasks: Vec<Task> = (0..new_runs).map(|_| adaptive.next_task()).collect();
+        for task in tasks {
+            self.enqueue(task);
+        }
+        self.task_available.notify_waiters();
+    }
+
+    #[must_use]
+    pub fn get_adaptive_graphs(&self, job_id: &str) -> Vec<&AdaptiveGraph> {
+        self.adaptive_graphs
+            .iter()
+            .filter(|adaptive| adaptive.job_id == job_id)
+            .collect()
+    }
+
+    /// Inicia uma corrida entre configurações candidatas. Cada rodada entra na
+    /// fila quando a anterior termina.
+    pub fn start_race(&mut self, config: RaceConfig, options: &GraphTaskOptions) -> Uuid {
+        self.create_job(&options.job_id);
+        for graph_id in config.graphs() {
+            self.apply_graph_options(graph_id, options);
+        }
+        let race = Race::new(
+            &options.job_id,
+            config,
+            options.priority,
+            options.requirements.clone(),
+        );
+        let race_id = race.id();
+        info!(
+            "Corrida {race_id} iniciada no job {} com {} candidatos",
+            options.job_id,
+            race.candidates().len()
+        );
+        self.races.push(race);
+        self.start_race_rounds();
+        race_id
+    }
+
+    #[must_use]
+    pub fn get_race(&self, race_id: Uuid) -> Option<&Race> {
+        self.races.iter().find(|race| race.id() == race_id)
+    }
+
+    #[must_use]
+    pub fn get_races(&self, job_id: &str) -> Vec<&Race> {
+        self.races
+            .iter()
+            .filter(|race| race.job_id() == job_id)
+            .collect()
+    }
+
+    fn start_race_rounds(&mut self) {
+        let mut tasks = Vec::new();
+        for race in &mut self.races {
+            if race.needs_round() {
+                tasks.extend(race.next_round_tasks());
+            }
+        }
+        if tasks.is_empty() {
+            return;
+        }
+        for task in tasks {
+            self.enqueue(task);
+        }
+        self.task_available.notify_waiters();
+    }
+
+    /// Registra uma fonte de tasks consumida sob demanda. A fila é mantida com
+    /// cerca de `source_buffer` tasks atribuíveis, e o status só passa a ser
+    /// acompanhado quando a task é materializada.
+    pub fn add_task_source<I>(&mut self, job_id: &str, tasks: I)
+    where
+        I: IntoIterator<Item = Task>,
+        I::IntoIter: Send + 'static,
+    {
+        info!("Fonte de tasks registrada no job {job_id}");
+        self.create_job(job_id);
+        let tasks: TaskIterator = Box::new(tasks.into_iter());
+        self.sources.push_back(TaskSource::new(job_id, tasks));
+        self.fill_from_sources();
+    }
+
+    /// Igual a `add_task_source`, com tasks tipadas. Tasks cuja configuração
+    /// não serializa são descartadas com um aviso.
+    pub fn add_typed_task_source<C, I>(&mut self, job_id: &str, tasks: I)
+    where
+        C: ConfigPayload + 'static,
+        I: IntoIterator<Item = Task<C>>,
+        I::IntoIter: Send + 'static,
+    {
+        self.add_task_source(job_id, tasks.into_iter().filter_map(encode_task));
+    }
+
+    pub fn set_source_buffer(&mut self, source_buffer: usize) {
+        self.source_buffer = source_buffer.max(1);
+        self.fill_from_sources();
+    }
+
+    #[must_use]
+    pub fn has_pending_sources(&self, job_id: &str) -> bool {
+        self.sources.iter().any(|source| source.job_id == job_id)
+    }
+
+    /// Se a task pode ser entregue a algum worker agora. Sem workers
+    /// conhecidos, toda task não pausada conta.
+    fn is_assignable(&self, task: &Task) -> bool {
+        !self.paused.is_paused(task) && (self.workers.is_empty() || self.has_eligible_worker(task))
+    }
+
+    fn has_eligible_worker(&self, task: &Task) -> bool {
+        self.workers.values().any(|worker| {
+            task.requirements.is_satisfied_by(&worker.capabilities)
+                && task.requirements.allows_worker(worker.worker_id)
+        })
+    }
+
+    /// Puxa tasks das fontes, alternando entre elas, até o buffer ter
+    /// `source_buffer` tasks atribuíveis. Fontes de jobs pausados esperam.
+    fn fill_from_sources(&mut self) {
+        if self.sources.is_empty() {
+            return;
+        }
+        let mut assignable = self
+            .pending_tasks
+            .iter()
+            .filter(|task| self.is_assignable(task))
+            .count();
+        let limit = self.source_buffer.saturating_mul(SOURCE_BUFFER_OVERFLOW);
+        let mut materialized = 0;
+        let mut exhausted = false;
+        let mut idle_sources = VecDeque::new();
+        while assignable < self.source_buffer && self.pending_tasks.len() < limit {
+            let Some(mut source) = self.sources.pop_front() else {
+                break;
+            };
+            if self.paused.is_job_paused(&source.job_id) {
+                idle_sources.push_back(source);
+                continue;
+            }
+            match source.next_task() {
+                Some(mut task) => {
+                    task.job_id.clone_from(&source.job_id);
+                    if self.is_assignable(&task) {
+                        assignable += 1;
+                    }
+                    self.enqueue(task);
+                    self.sources.push_back(source);
+                    materialized += 1;
+                }
+                None => {
+                    info!("Fonte de tasks do job {} esgotada", source.job_id);
+                    exhausted = true;
+                }
+            }
+        }
+        self.sources.append(&mut idle_sources);
+
+        if materialized > 0 {
+            debug!("{materialized} tasks materializadas a partir das fontes");
+            self.task_available.notify_waiters();
+        }
+        if exhausted {
+            self.release_ready_stages();
+        }
+    }
+
+    fn apply_graph_options(&mut self, graph_id: &str, options: &GraphTaskOptions) {
+        if let Some(graph_priority) = options.graph_priority {
+            self.set_graph_priority(&options.job_id, graph_id, graph_priority);
+        }
+        if let Some(objective) = options.objective {
+            self.set_graph_objective(&options.job_id, graph_id, objective);
+        }
+    }
+
+    fn enqueue(&mut self, task: Task) {
+        for stage in &mut self.stages {
+            if stage.waits_for_graph(&task) {
+                stage.upstream.insert(task.id);
+            }
+        }
+        self.push_pending(task);
+    }
+
+    /// Enfileira sem registrar a task como dependência de estágios; usado
+    /// pelas tasks que os próprios estágios liberam.
+    fn push_pending(&mut self, mut task: Task) {
+        let job = match self.jobs.get(&task.job_id) {
+            Some(job) => job,
+            None => self.jobs.entry(task.job_id.clone()).or_default(),
+        };
+        task.seed = derive_task_seed(job.master_seed, &task.graph_id, task.run_number);
+        task.objective = job
+            .graph_objectives
+            .get(&task.graph_id)
+            .copied()
+            .unwrap_or(job.objective);
+        if let Some(directions) = job.objective_directions.get(&task.graph_id) {
+            task.objective_directions.clone_from(directions);
+        }
+        if task.priority != 0 {
+            self.priorities.mark_in_use();
+        }
+        self.set_status(&task, TaskStatus::Pending);
+        self.priorities.enqueued(task.id);
+        self.pending_tasks.push_back(task);
+    }
+
+    /// Adiciona tasks que só entram na fila quando todas as dependências
+    /// terminarem. Retorna o id do estágio.
+    pub fn add_dependent_tasks(
+        &mut self,
+        job_id: &str,
+        dependencies: Vec<Dependency>,
+        mut tasks: Vec<Task>,
+    ) -> Uuid {
+        self.create_job(job_id);
+        for task in &mut tasks {
+            task.job_id = job_id.to_string();
+            self.set_status(task, TaskStatus::Waiting);
+        }
+        self.add_stage(job_id, dependencies, Downstream::Tasks(tasks))
+    }
+
+    /// Igual a `add_dependent_tasks`, com tasks tipadas.
+    pub fn add_typed_dependent_tasks<C: ConfigPayload>(
+        &mut self,
+        job_id: &str,
+        dependencies: Vec<Dependency>,
+        tasks: Vec<Task<C>>,
+    ) -> Result<Uuid, PayloadError> {
+        let tasks = tasks
+            .into_iter()
+            .map(Task::into_untyped)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self.add_dependent_tasks(job_id, dependencies, tasks))
+    }
+
+    /// Registra um estágio cujas tasks são geradas por `generator` a partir dos
+    /// resultados das dependências, quando todas terminarem.
+    ///
+    /// Apenas resultados recebidos depois do registro são repassados ao
+    /// gerador, então o estágio deve ser registrado antes das dependências
+    /// começarem a concluir.
+    pub fn add_follow_up<F>(
+        &mut self,
+        job_id: &str,
+        dependencies: Vec<Dependency>,
+        generator: F,
+    ) -> Uuid
+    where
+        F: FnMut(&[TaskResult]) -> Vec<Task> + Send + 'static,
+    {
+        self.create_job(job_id);
+        let generator: FollowUpGenerator = Box::new(generator);
+        self.add_stage(job_id, dependencies, Downstream::Generator(generator))
+    }
+
+    /// Igual a `add_follow_up`, com resultados e tasks tipados. Resultados que
+    /// não desserializam não chegam ao gerador, e tasks cuja configuração não
+    /// serializa são descartadas, ambos com um aviso.
+    pub fn add_typed_follow_up<C, S, F>(
+        &mut self,
+        job_id: &str,
+        dependencies: Vec<Dependency>,
+        mut generator: F,
+    ) -> Uuid
+    where
+        C: ConfigPayload,
+        S: SolutionPayload,
+        F: FnMut(&[TaskResult<S>]) -> Vec<Task<C>> + Send + 'static,
+    {
+        self.add_follow_up(job_id, dependencies, move |results| {
+            let results: Vec<TaskResult<S>> = results
+                .iter()
+                .filter_map(|result| {
+                    let task_id = result.task_id;
+                    result
+                        .clone()
+                        .into_typed()
+                        .inspect_err(|e| {
+                            warn!("Resultado da task {task_id} ignorado pelo estágio: {e}");
+                        })
+                        .ok()
+                })
+                .collect();
+            generator(&results)
+                .into_iter()
+                .filter_map(encode_task)
+                .collect()
+        })
+    }
+
+    fn add_stage(
+        &mut self,
+        job_id: &str,
+        dependencies: Vec<Dependency>,
+        downstream: Downstream,
+    ) -> Uuid {
+        let stage_id = Uuid::new_v4();
+        let own_tasks: HashSet<Uuid> = match &downstream {
+            Downstream::Tasks(tasks) => tasks.iter().map(|task| task.id).collect(),
+            Downstream::Generator(_) => HashSet::new(),
+        };
+        let graph_tasks = self.jobs.get(job_id).map(|job| &job.graph_tasks);
+        let mut upstream = HashSet::new();
+        for dependency in &dependencies {
+            match dependency {
+                Dependency::Task(task_id) => {
+                    upstream.insert(*task_id);
                 }
+                Dependency::AllRunsOfGraph(graph_id) => upstream.extend(
+                    graph_tasks
+                        .and_then(|graphs| graphs.get(graph_id))
+                        .into_iter()
+                        .flatten()
+                        .filter(|task_id| !own_tasks.contains(task_id)),
+                ),
             }
+        }
+
+        info!(
+            "Estágio {stage_id} do job {job_id} registrado com {} dependências",
+            dependencies.len()
+        );
+        self.stages.push(Stage {
+            id: stage_id,
+            job_id: job_id.to_string(),
+            dependencies,
+            downstream,
+            upstream,
+            upstream_results: Vec::new(),
+        });
+        self.release_ready_stages();
+        stage_id
+    }
+
+    fn is_stage_ready(&self, stage: &Stage) -> bool {
+        let Some(job) = self.jobs.get(&stage.job_id) else {
+            return false;
+        };
+        // Uma fonte, um graph adaptativo ou uma corrida ainda ativos podem
+        // gerar mais execuções do graph.
+        let more_runs_coming = stage.dependencies.iter().any(|dependency| {
+            let Dependency::AllRunsOfGraph(graph_id) = dependency else {
+                return false;
+            };
+            self.has_pending_sources(&stage.job_id)
+                || self.adaptive_graphs.iter().any(|adaptive| {
+                    adaptive.job_id == stage.job_id
+                        && adaptive.graph_id == *graph_id
+                        && !adaptive.is_finished()
+                })
+                || self
+                    .races
+                    .iter()
+                    .any(|race| race.job_id() == stage.job_id && race.runs_graph(graph_id))
+        });
+
+        !more_runs_coming
+            && stage.upstream.iter().all(|task_id| {
+                matches!(
+                    job.tasks_status.get(task_id),
+                    Some(TaskStatus::Completed | TaskStatus::Aborted | TaskStatus::Failed)
+                )
+            })
+    }
+
+    fn release_ready_stages(&mut self) {
+        let (ready, waiting): (Vec<Stage>, Vec<Stage>) = std::mem::take(&mut self.stages)
+            .into_iter()
+            .partition(|stage| self.is_stage_ready(stage));
+        self.stages = waiting;
+
+        if ready.is_empty() {
+            return;
+        }
+
+        for stage in ready {
+            let tasks = match stage.downstream {
+                Downstream::Tasks(tasks) => tasks,
+                Downstream::Generator(mut generator) => generator(&stage.upstream_results),
+            };
+            info!(
+                "Dependências do estágio {} satisfeitas, liberando {} tasks",
+                stage.id,
+                tasks.len()
+            );
+            for mut task in tasks {
+                task.job_id.clone_from(&stage.job_id);
+                self.push_pending(task);
+            }
+        }
+        self.task_available.notify_waiters();
+    }
+
+    pub fn create_job(&mut self, job_id: &str) {
+        if !self.jobs.contains_key(job_id) {
+            info!("Job {job_id} criado");
+            self.jobs.insert(job_id.to_string(), Job::default());
+        }
+    }
+
+    /// Define a semente mestre do job, criando-o se preciso. Só afeta tasks
+    /// enfileiradas depois da chamada.
+    pub fn set_job_seed(&mut self, job_id: &str, master_seed: u64) {
+        self.create_job(job_id);
+        if let Some(job) = self.jobs.get_mut(job_id) {
+            if !job.tasks_status.is_empty() {
+                warn!("Semente do job {job_id} alterada depois de tasks já criadas");
+            }
+            job.master_seed = master_seed;
+        }
+    }
+
+    /// Direção padrão da otimização nos graphs do job.
+    pub fn set_objective(&mut self, job_id: &str, objective: Objective) {
+        self.create_job(job_id);
+        if let Some(job) = self.jobs.get_mut(job_id) {
+            job.objective = objective;
+        }
+    }
+
+    pub fn set_graph_objective(&mut self, job_id: &str, graph_id: &str, objective: Objective) {
+        self.create_job(job_id);
+        if let Some(job) = self.jobs.get_mut(job_id) {
+            job.graph_objectives.insert(graph_id.to_string(), objective);
+        }
+    }
+
+    #[must_use]
+    pub fn get_objective(&self, job_id: &str, graph_id: &str) -> Objective {
+        self.jobs
+            .get(job_id)
+            .map_or_else(Objective::default, |job| {
+                job.graph_objectives
+                    .get(graph_id)
+                    .copied()
+                    .unwrap_or(job.objective)
+            })
+    }
+
+    /// Direções dos objetivos de um graph multiobjetivo; objetivos além da
+    /// lista seguem a direção do graph.
+    pub fn set_objective_directions(
+        &mut self,
+        job_id: &str,
+        graph_id: &str,
+        directions: Vec<Objective>,
+    ) {
+        self.create_job(job_id);
+        if let Some(job) = self.jobs.get_mut(job_id) {
+            job.objective_directions
+                .insert(graph_id.to_string(), directions);
+        }
+    }
+
+    /// Direções configuradas para os `dimensions` objetivos do graph.
+    #[must_use]
+    pub fn get_objective_directions(
+        &self,
+        job_id: &str,
+        graph_id: &str,
+        dimensions: usize,
+    ) -> Vec<Objective> {
+        let objective = self.get_objective(job_id, graph_id);
+        let directions = self
+            .jobs
+            .get(job_id)
+            .and_then(|job| job.objective_directions.get(graph_id))
+            .map_or(&[][..], Vec::as_slice);
+        (0..dimensions)
+            .map(|dimension| directions.get(dimension).copied().unwrap_or(objective))
+            .collect()
+    }
+
+    /// Ponto de referência para o hipervolume da frente de Pareto do graph.
+    pub fn set_reference_point(&mut self, job_id: &str, graph_id: &str, reference_point: Vec<f64>) {
+        self.create_job(job_id);
+        if let Some(job) = self.jobs.get_mut(job_id) {
+            job.reference_points
+                .insert(graph_id.to_string(), reference_point);
+        }
+    }
+
+    #[must_use]
+    pub fn get_reference_point(&self, job_id: &str, graph_id: &str) -> Option<&[f64]> {
+        self.jobs
+            .get(job_id)
+            .and_then(|job| job.reference_points.get(graph_id))
+            .map(Vec::as_slice)
+    }
+
+    #[must_use]
+    pub fn get_job_objective(&self, job_id: &str) -> Objective {
+        self.jobs
+            .get(job_id)
+            .map_or_else(Objective::default, |job| job.objective)
+    }
+
+    #[must_use]
+    pub fn get_job_seed(&self, job_id: &str) -> Option<u64> {
+        self.jobs.get(job_id).map(|job| job.master_seed)
+    }
+
+    pub fn get_job_ids(&self) -> Vec<&str> {
+        self.jobs.keys().map(String::as_str).collect()
+    }
+
+    pub fn pause_job(&mut self, job_id: &str) {
+        if self.paused.pause_job(job_id) {
+            info!("Job {job_id} pausado");
+        }
+    }
+
+    pub fn resume_job(&mut self, job_id: &str) {
+        if self.paused.resume_job(job_id) {
+            info!("Job {job_id} retomado");
+            self.fill_from_sources();
+            self.task_available.notify_waiters();
+        }
+    }
+
+    pub fn pause_graph(&mut self, job_id: &str, graph_id: &str) {
+        if self.paused.pause_graph(job_id, graph_id) {
+            info!("Graph {graph_id} do job {job_id} pausado");
+        }
+    }
+
+    pub fn resume_graph(&mut self, job_id: &str, graph_id: &str) {
+        if self.paused.resume_graph(job_id, graph_id) {
+            info!("Graph {graph_id} do job {job_id} retomado");
+            self.fill_from_sources();
+            self.task_available.notify_waiters();
+        }
+    }
+
+    pub fn get_paused_work(&self) -> &PausedWork {
+        &self.paused
+    }
+
+    /// Descarta as tasks pendentes do job e cancela as atribuídas; resultados
+    /// que chegarem depois para elas são ignorados.
+    pub fn abort_job(&mut self, job_id: &str) -> AbortRecord {
+        self.abort_matching(job_id, None)
+    }
+
+    pub fn abort_graph(&mut self, job_id: &str, graph_id: &str) -> AbortRecord {
+        self.abort_matching(job_id, Some(graph_id))
+    }
+
+    pub fn get_aborts(&self, job_id: &str) -> &[AbortRecord] {
+        self.jobs.get(job_id).map_or(&[], |job| &job.aborts)
+    }
+
+    fn abort_matching(&mut self, job_id: &str, graph_id: Option<&str>) -> AbortRecord {
+        let matches = |task: &Task| {
+            task.job_id == job_id && graph_id.is_none_or(|graph_id| task.graph_id == graph_id)
+        };
+
+        let mut dropped = Vec::new();
+        let mut kept = VecDeque::with_capacity(self.pending_tasks.len());
+        for task in self.pending_tasks.drain(..) {
+            if matches(&task) {
+                dropped.push(task);
+            } else {
+                kept.push_back(task);
+            }
+        }
+        self.pending_tasks = kept;
+
+        let cancelled_ids: Vec<Uuid> = self
+            .assigned_tasks
+            .iter()
+            .filter(|(_, (task, _))| matches(task))
+            .map(|(&task_id, _)| task_id)
+            .collect();
+
+        for task in &dropped {
+            self.pending_verifications.remove(&task.id);
+            self.priorities.dequeued(task.id);
+            self.set_status(task, TaskStatus::Aborted);
+        }
+        for task_id in &cancelled_ids {
+            if let Some((task, _)) = self.assigned_tasks.remove(task_id) {
+                self.pending_verifications.remove(task_id);
+                self.release_running(&task, false);
+                self.set_status(&task, TaskStatus::Aborted);
+            }
+        }
+
+        // Tasks ainda esperando dependências também são descartadas; um job
+        // abortado por inteiro perde ainda os estágios gerados dinamicamente.
+        let mut waiting_dropped = Vec::new();
+        let mut stages = std::mem::take(&mut self.stages);
+        stages.retain_mut(|stage| {
+            if stage.job_id != job_id {
+                return true;
+            }
+            match &mut stage.downstream {
+                Downstream::Tasks(tasks) => {
+                    let (aborted, kept): (Vec<Task>, Vec<Task>) =
+                        tasks.drain(..).partition(|task| matches(task));
+                    waiting_dropped.extend(aborted);
+                    *tasks = kept;
+                    graph_id.is_some() && !tasks.is_empty()
+                }
+                Downstream::Generator(_) => graph_id.is_some(),
+            }
+        });
+        self.stages = stages;
+        for adaptive in &mut self.adaptive_graphs {
+            if adaptive.job_id == job_id
+                && !adaptive.is_finished()
+                && graph_id.is_none_or(|graph_id| adaptive.graph_id == graph_id)
+            {
+                adaptive.stopped = Some(AdaptiveStop::Aborted);
+            }
+        }
+        for task in &waiting_dropped {
+            self.set_status(task, TaskStatus::Aborted);
+        }
+
+        match graph_id {
+            Some(graph_id) => {
+                for source in &mut self.sources {
+                    if source.job_id == job_id {
+                        source.skipped_graphs.insert(graph_id.to_string());
+                    }
+                }
+                for race in &mut self.races {
+                    if race.job_id() == job_id {
+                        race.drop_graph(graph_id);
+                    }
+                }
+            }
+            None => {
+                self.sources.retain(|source| source.job_id != job_id);
+                for race in &mut self.races {
+                    if race.job_id() == job_id {
+                        race.stop();
+                    }
+                }
+            }
+        }
+
+        let record = AbortRecord {
+            graph_id: graph_id.map(str::to_string),
+            aborted_at_unix_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_millis() as u64),
+            pending_dropped: dropped.len() + waiting_dropped.len(),
+            assigned_cancelled: cancelled_ids.len(),
+        };
+        warn!(
+            "Abortando job {job_id}{}: {} tasks pendentes descartadas, {} atribuídas canceladas",
+            graph_id.map_or_else(String::new, |graph_id| format!(" (graph {graph_id})")),
+            record.pending_dropped,
+            record.assigned_cancelled
+        );
+        self.jobs
+            .entry(job_id.to_string())
+            .or_default()
+            .aborts
+            .push(record.clone());
+
+        // Tasks abortadas contam como terminadas para as dependências.
+        self.start_race_rounds();
+        self.release_ready_stages();
+        self.fill_from_sources();
+        record
+    }
+
+    fn set_status(&mut self, task: &Task, status: TaskStatus) {
+        // Reexecuções de verificação não contam como tasks do job.
+        if task.verifies.is_some() {
+            return;
+        }
+        let job = match self.jobs.get_mut(&task.job_id) {
+            Some(job) => job,
+            None => self.jobs.entry(task.job_id.clone()).or_default(),
+        };
+        let previous = job.tasks_status.insert(task.id, status);
+        if previous.is_none() {
+            job.graph_tasks
+                .entry(task.graph_id.clone())
+                .or_default()
+                .insert(task.id);
+        }
+
+        let progress = match job.graph_progress.get_mut(&task.graph_id) {
+            Some(progress) => progress,
+            None => job.graph_progress.entry(task.graph_id.clone()).or_default(),
+        };
+        match previous {
+            None => progress.total += 1,
+            Some(TaskStatus::Completed) => progress.completed -= 1,
+            Some(TaskStatus::Aborted) => progress.aborted -= 1,
+            Some(TaskStatus::Failed) => progress.failed -= 1,
+            Some(_) => {}
+        }
+        match status {
+            TaskStatus::Completed => progress.completed += 1,
+            TaskStatus::Aborted => progress.aborted += 1,
+            TaskStatus::Failed => progress.failed += 1,
+            _ => {}
+        }
+    }
+
+    pub fn get_graph_progress(&self, job_id: &str, graph_id: &str) -> GraphProgress {
+        self.jobs
+            .get(job_id)
+            .and_then(|job| job.graph_progress.get(graph_id))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn set_graph_priority(&mut self, job_id: &str, graph_id: &str, priority: i32) {
+        info!("Prioridade do graph {graph_id} do job {job_id} definida como {priority}");
+        self.priorities
+            .set_graph_priority(job_id, graph_id, priority);
+    }
+
+    /// Task em execução, se estiver atribuída a `worker_id`.
+    #[must_use]
+    pub fn get_assigned_task(&self, task_id: Uuid, worker_id: Uuid) -> Option<&Task> {
+        self.assigned_tasks
+            .get(&task_id)
+            .filter(|(_, lease)| lease.worker_id == worker_id)
+            .map(|(task, _)| task)
+    }
+
+    /// Altera a prioridade de uma task ainda não concluída. Retorna `false` se
+    /// a task não estiver pendente nem atribuída.
+    pub fn set_task_priority(&mut self, task_id: Uuid, priority: i32) -> bool {
+        let task = match self.assigned_tasks.get_mut(&task_id) {
+            Some((task, _)) => Some(task),
+            None => self
+                .pending_tasks
+                .iter_mut()
+                .find(|task| task.id == task_id),
         };
 
+        if let Some(task) = task {
+            task.priority = priority;
+            self.priorities.mark_in_use();
+            true
+        } else {
+            warn!("Tentando alterar a prioridade de uma task inexistente ou concluída: {task_id}");
+            false
+        }
+    }
+
+    /// A cada `interval` esperando na fila, uma task ganha um ponto de
+    /// prioridade, evitando que tasks de baixa prioridade esperem para sempre.
+    pub fn set_priority_aging(&mut self, interval: Option<Duration>) {
+        let tasks_prioritized = self.pending_tasks.iter().any(|task| task.priority != 0);
+        self.priorities.set_aging(interval, tasks_prioritized);
+    }
+
+    pub fn get_priorities(&self) -> &Priorities {
+        &self.priorities
+    }
+
+    fn release_running(&mut self, task: &Task, completed: bool) {
+        if let Some(load) = self.graph_loads.get_mut(&task.job_id, &task.graph_id) {
+            load.running = load.running.saturating_sub(1);
+            if completed {
+                load.completed += 1;
+            }
+        }
+    }
+
+    fn requeue(&mut self, task: Task) {
+        self.release_running(&task, false);
+        self.set_status(&task, TaskStatus::Pending);
+        self.priorities.enqueued(task.id);
+        self.pending_tasks.push_front(task);
+    }
+
+    pub fn set_scheduling_policy(&mut self, scheduling_policy: Box<dyn SchedulingPolicy>) {
+        self.scheduling_policy = scheduling_policy;
+    }
+
+    /// Tasks atribuídas há mais tempo que `timeout` sem resultado voltam para a fila.
+    pub fn set_lease_timeout(&mut self, timeout: Option<Duration>) {
+        self.lease_timeout = timeout;
+    }
+
+    pub fn get_next_task(&mut self, worker_id: Uuid) -> Option<Task> {
+        self.reclaim_expired_leases();
+
+        let worker = self
+            .workers
+            .entry(worker_id)
+            .or_insert_with(|| WorkerInfo::new(worker_id));
+        let ctx = SchedulingContext::new(
+            &self.pending_tasks,
+            worker,
+            &self.priorities,
+            &self.graph_loads,
+            &self.runtimes,
+            &self.paused,
+        );
+        let task = self
+            .scheduling_policy
+            .select(&ctx)
+            .and_then(|index| self.pending_tasks.remove(index));
+
         if let Some(task) = task {
             info!("Task {} atribuida ao woerker {}", task.id, worker_id);
-            self.assigned_tasks
-                .insert(task.id, (task.clone(), worker_id));
-            self.all_tasks_status.insert(task.id, TaskStatus::Assigned);
+            worker.record_assignment(&task);
+            self.priorities.dequeued(task.id);
+            self.graph_loads.entry(&task.job_id, &task.graph_id).running += 1;
+            let assigned_at = Instant::now();
+            let lease = TaskLease {
+                worker_id,
+                assigned_at,
+                expires_at: self.lease_timeout.map(|timeout| assigned_at + timeout),
+            };
+            self.set_status(&task, TaskStatus::Assigned);
+            self.assigned_tasks.insert(task.id, (task.clone(), lease));
+            self.fill_from_sources();
             Some(task)
         } else {
             debug!("Não existem tasks pendentes.");
+            // A fila pode estar cheia de tasks que este worker não recebe.
+            self.fill_from_sources();
             None
         }
     }
 
+    pub fn get_next_tasks(&mut self, worker_id: Uuid, max_tasks: usize) -> Vec<Task> {
+        let mut tasks = Vec::with_capacity(max_tasks.min(self.pending_tasks.len()));
+        while tasks.len() < max_tasks {
+            match self.get_next_task(worker_id) {
+                Some(task) => tasks.push(task),
+                None => break,
+            }
+        }
+        tasks
+    }
+
+    pub fn renew_leases(&mut self, worker_id: Uuid) {
+        let Some(timeout) = self.lease_timeout else {
+            return;
+        };
+
+        let expires_at = Instant::now() + timeout;
+        for (_, lease) in self.assigned_tasks.values_mut() {
+            if lease.worker_id == worker_id {
+                lease.expires_at = Some(expires_at);
+            }
+        }
+    }
+
+    /// Devolve para a fila as tasks cujo lease expirou, retornando seus ids.
+    pub fn reclaim_expired_leases(&mut self) -> Vec<Uuid> {
+        let now = Instant::now();
+        let expired: Vec<Uuid> = self
+            .assigned_tasks
+            .iter()
+            .filter(|(_, (_, lease))| lease.expires_at.is_some_and(|expires| expires <= now))
+            .map(|(&task_id, _)| task_id)
+            .collect();
+
+        for task_id in &expired {
+            if let Some((task, lease)) = self.assigned_tasks.remove(task_id) {
+                warn!(
+                    "Lease da task {task_id} expirou no worker {}, devolvendo para a fila",
+                    lease.worker_id
+                );
+                self.requeue(task);
+            }
+        }
+
+        if !expired.is_empty() {
+            self.task_available.notify_waiters();
+        }
+        expired
+    }
+
+    pub fn get_lease(&self, task_id: Uuid) -> Option<&TaskLease> {
+        self.assigned_tasks.get(&task_id).map(|(_, lease)| lease)
+    }
+
+    /// Marca a task do resultado como concluída e registra seu tempo de
+    /// processamento para as estimativas de duração.
+    /// Retorna a task concluída, para que o resultado seja associado ao seu job.
+    /// Só o worker que detém o lease pode concluí-la.
+    pub fn complete_task(&mut self, result: &TaskResult) -> Result<Task, Box<dyn Error>> {
+        if let Some((_, lease)) = self.assigned_tasks.get(&result.task_id)
+            && lease.worker_id != result.worker_id
+        {
+            warn!(
+                "Worker {} tentou concluir a task {}, atribuída ao worker {}",
+                result.worker_id, result.task_id, lease.worker_id
+            );
+            return Err(format!(
+                "Task {} não está atribuída ao worker {}",
+                result.task_id, result.worker_id
+            )
+            .into());
+        }
+        let (task, worker_id) = self.take_assigned_for_completion(result.task_id)?;
+        self.runtimes.record(
+            &task.job_id,
+            &task.graph_id,
+            &task.ag_config,
+            result.processing_time_ms,
+        );
+
+        if task.verifies.is_some() {
+            self.record_verification(&task, worker_id, result);
+            return Ok(task);
+        }
+        if self.verification_rate > 0.0 && rand::random::<f64>() < self.verification_rate {
+            self.schedule_verification(&task, worker_id, result);
+        }
+
+        for stage in &mut self.stages {
+            if stage.depends_on(&task) {
+                stage.upstream_results.push(result.clone());
+            }
+        }
+        // A próxima rodada precisa estar na fila, ou a corrida encerrada,
+        // antes de conferir os estágios que esperam pelo graph.
+        if let Some(race) = self.races.iter_mut().find(|race| race.owns(task.id)) {
+            race.record(task.id, result.fitness, task.objective);
+            self.start_race_rounds();
+        }
+        self.release_ready_stages();
+        Ok(task)
+    }
+
+    /// Fração das tasks concluídas que é reexecutada por outro worker para
+    /// conferir se o resultado se repete. Zero desativa a verificação.
+    ///
+    /// A reexecução só vai para um worker diferente do que detinha o lease; sem
+    /// outro worker capaz registrado, a verificação é descartada.
+    pub fn set_verification_rate(&mut self, rate: f64) {
+        self.verification_rate = rate.clamp(0.0, 1.0);
+    }
+
+    fn schedule_verification(&mut self, task: &Task, worker_id: Uuid, result: &TaskResult) {
+        let mut verification = task.clone();
+        verification.id = Uuid::new_v4();
+        verification.verifies = Some(task.id);
+        verification.requirements = verification.requirements.with_excluded_worker(worker_id);
+        if !self.has_eligible_worker(&verification) {
+            debug!(
+                "Task {} não será verificada: nenhum outro worker pode reexecutá-la",
+                task.id
+            );
+            return;
+        }
+        debug!(
+            "Task {} será reexecutada como {} para verificação",
+            task.id, verification.id
+        );
+
+        self.pending_verifications
+            .insert(verification.id, (worker_id, result.clone()));
+        self.priorities.enqueued(verification.id);
+        self.pending_tasks.push_back(verification);
+        self.task_available.notify_waiters();
+    }
+
+    fn record_verification(&mut self, task: &Task, worker_id: Uuid, result: &TaskResult) {
+        let Some((original_worker, original)) = self.pending_verifications.remove(&task.id) else {
+            return;
+        };
+        let record =
+            VerificationRecord::compare(task, original_worker, &original, worker_id, result);
+        if record.is_match() {
+            debug!(
+                "Task {} reproduzida pelo worker {}",
+                record.task_id, record.verifying_worker
+            );
+        } else {
+            warn!(
+                "Resultado divergente na task {}: worker {} obteve {} e worker {} obteve {} (solução {})",
+                record.task_id,
+                record.original_worker,
+                record.original_fitness,
+                record.verifying_worker,
+                record.verifying_fitness,
+                if record.solution_matches {
+                    "igual"
+                } else {
+                    "diferente"
+                }
+            );
+        }
+        self.jobs
+            .entry(task.job_id.clone())
+            .or_default()
+            .verifications
+            .push(record);
+    }
+
+    #[must_use]
+    pub fn get_verifications(&self, job_id: &str) -> &[VerificationRecord] {
+        self.jobs
+            .get(job_id)
+            .map_or(&[], |job| job.verifications.as_slice())
+    }
+
     pub fn mark_task_completed(&mut self, task_id: Uuid) -> Result<(), Box<dyn Error>> {
-        if let Some((_, worker_id)) = self.assigned_tasks.remove(&task_id) {
-            info!("Task {task_id} finalizada pelo worker {worker_id}");
-            self.all_tasks_status.insert(task_id, TaskStatus::Completed);
-            Ok(())
+        self.take_assigned_for_completion(task_id)?;
+        self.release_ready_stages();
+        Ok(())
+    }
+
+    /// Registra uma duração conhecida, por exemplo de um relatório anterior,
+    /// para alimentar as estimativas antes de qualquer resultado chegar.
+    pub fn record_runtime(
+        &mut self,
+        job_id: &str,
+        graph_id: &str,
+        ag_config: &str,
+        processing_time_ms: u64,
+    ) {
+        self.runtimes
+            .record(job_id, graph_id, ag_config, processing_time_ms);
+    }
+
+    pub fn get_runtime_estimates(&self) -> &RuntimeEstimates {
+        &self.runtimes
+    }
+
+    /// Retorna a task e o worker que detinha o lease.
+    fn take_assigned_for_completion(
+        &mut self,
+        task_id: Uuid,
+    ) -> Result<(Task, Uuid), Box<dyn Error>> {
+        if let Some((task, lease)) = self.assigned_tasks.remove(&task_id) {
+            info!("Task {task_id} finalizada pelo worker {}", lease.worker_id);
+            self.failed_attempts.remove(&task_id);
+            self.release_running(&task, true);
+            if let Some(worker) = self.workers.get_mut(&lease.worker_id) {
+                worker.tasks_completed += 1;
+            }
+            self.set_status(&task, TaskStatus::Completed);
+            Ok((task, lease.worker_id))
         } else {
             warn!("Tentando marcar uma task não atribuida: {task_id}");
             Err(format!("Task {task_id} não foi achada entre as tasks atribuidas").into())
         }
     }
 
+    /// Quantas vezes uma task pode falhar antes de ser descartada como `Failed`.
+    pub fn set_max_task_attempts(&mut self, attempts: u32) {
+        self.max_task_attempts = attempts.max(1);
+    }
+
+    /// Falha reportada por um worker. Só o worker que detém o lease pode
+    /// reportá-la.
+    pub fn fail_task(
+        &mut self,
+        task_id: Uuid,
+        worker_id: Uuid,
+        reason: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        if self.get_assigned_task(task_id, worker_id).is_none() {
+            return Err(format!("Task {task_id} não está atribuída ao worker {worker_id}").into());
+        }
+        warn!("Worker {worker_id} não conseguiu executar a task {task_id}: {reason}");
+        self.mark_task_failed(task_id);
+        Ok(())
+    }
+
+    /// Devolve a task para a fila, ou a descarta se já esgotou as tentativas.
     pub fn mark_task_failed(&mut self, task_id: Uuid) {
-        if let Some((task, _)) = self.assigned_tasks.remove(&task_id) {
-            error!("Task {task_id} falhou");
-            self.pending_tasks.push_front(task.clone());
-            self.all_tasks_status.insert(task_id, TaskStatus::Failed);
-        } else {
+        let Some((task, lease)) = self.assigned_tasks.remove(&task_id) else {
             warn!("Tentando marcar uma task não atribuida: {task_id}");
+            return;
+        };
+        if let Some(worker) = self.workers.get_mut(&lease.worker_id) {
+            worker.tasks_failed += 1;
+        }
+        let attempts = self.failed_attempts.entry(task_id).or_default();
+        *attempts += 1;
+        if *attempts < self.max_task_attempts {
+            warn!(
+                "Task {task_id} falhou ({attempts} de {} tentativas), devolvendo para a fila",
+                self.max_task_attempts
+            );
+            self.requeue(task);
+        } else {
+            error!("Task {task_id} falhou {attempts} vezes e foi descartada");
+            self.failed_attempts.remove(&task_id);
+            self.release_running(&task, false);
+            self.pending_verifications.remove(&task_id);
+            self.set_status(&task, TaskStatus::Failed);
+            self.discard_failed_run(&task);
+            self.release_ready_stages();
+        }
+        self.fill_from_sources();
+        self.task_available.notify_waiters();
+    }
+
+    /// Corridas e graphs adaptativos esperam o resultado de cada execução;
+    /// uma execução descartada não pode segurá-los.
+    fn discard_failed_run(&mut self, task: &Task) {
+        if let Some(race) = self.races.iter_mut().find(|race| race.owns(task.id)) {
+            race.discard(task.id);
+            self.start_race_rounds();
+        }
+        let Some(adaptive) = self
+            .adaptive_graphs
+            .iter_mut()
+            .find(|adaptive| adaptive.task_ids.contains(&task.id))
+        else {
+            return;
+        };
+        if adaptive.record_failure() {
+            let replacement = adaptive.next_task();
+            self.enqueue(replacement);
+        }
+    }
+
+    /// Notificado sempre que novas tasks entram na fila pendente.
+    pub fn task_notifier(&self) -> Arc<Notify> {
+        Arc::clone(&self.task_available)
+    }
+
+    pub fn register_worker(&mut self, worker_id: Uuid, capabilities: WorkerCapabilities) {
+        info!("Worker {worker_id} registrado com capacidades {capabilities:?}");
+        self.workers
+            .entry(worker_id)
+            .or_insert_with(|| WorkerInfo::new(worker_id))
+            .capabilities = capabilities;
+    }
+
+    pub fn get_graph_load(&self, job_id: &str, graph_id: &str) -> GraphLoad {
+        self.graph_loads
+            .get(job_id, graph_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn get_worker(&self, worker_id: Uuid) -> Option<&WorkerInfo> {
+        self.workers.get(&worker_id)
+    }
+
+    pub fn get_workers(&self) -> &HashMap<Uuid, WorkerInfo> {
+        &self.workers
+    }
+
+    pub fn get_total_tasks(&self, job_id: &str) -> usize {
+        self.get_tasks_status(job_id).map_or(0, HashMap::len)
+    }
+
+    pub fn get_completed_tasks_count(&self, job_id: &str) -> usize {
+        self.count_tasks_with_status(job_id, TaskStatus::Completed)
+    }
+
+    pub fn count_tasks_with_status(&self, job_id: &str, status: TaskStatus) -> usize {
+        self.get_tasks_status(job_id).map_or(0, |tasks_status| {
+            tasks_status.values().filter(|&&s| s == status).count()
+        })
+    }
+
+    pub fn get_tasks_status(&self, job_id: &str) -> Option<&HashMap<Uuid, TaskStatus>> {
+        self.jobs.get(job_id).map(|job| &job.tasks_status)
+    }
+}
+
+fn encode_task<C: ConfigPayload>(task: Task<C>) -> Option<Task> {
+    let task_id = task.id;
+    task.into_untyped()
+        .inspect_err(|e| warn!("Task {task_id} descartada: configuração inválida: {e}"))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    fn result(task: &Task, worker_id: Uuid, fitness: f64) -> TaskResult {
+        TaskResult {
+            task_id: task.id,
+            graph_id: task.graph_id.clone(),
+            worker_id,
+            fitness,
+            objectives: Vec::new(),
+            solution_data: Vec::new(),
+            interations_run: 1,
+            processing_time_ms: 1,
+            seed: task.seed,
+        }
+    }
+
+    /// Atribui e conclui tudo o que estiver na fila, retornando as tasks.
+    fn run_pending(tm: &mut TaskManager, worker_id: Uuid) -> Vec<Task> {
+        let tasks = tm.get_next_tasks(worker_id, usize::MAX);
+        for task in &tasks {
+            tm.complete_task(&result(task, worker_id, f64::from(task.run_number)))
+                .unwrap();
+        }
+        tasks
+    }
+
+    fn status(tm: &TaskManager, task_id: Uuid) -> Option<TaskStatus> {
+        tm.get_tasks_status(DEFAULT_JOB_ID)?.get(&task_id).copied()
+    }
+
+    #[test]
+    fn stage_waits_for_every_race_round() {
+        let mut tm = TaskManager::new(DistributionStrategy::Fifo);
+        let config = RaceConfig::new(["g"])
+            .with_candidate("a")
+            .with_candidate("b")
+            .with_budget(6);
+        let race_id = tm.start_race(config, &GraphTaskOptions::default());
+        let after = Task::new("after".to_string(), 0, "{}".to_string());
+        let after_id = after.id;
+        tm.add_dependent_tasks(
+            DEFAULT_JOB_ID,
+            vec![Dependency::AllRunsOfGraph("g".to_string())],
+            vec![after],
+        );
+        let worker_id = Uuid::new_v4();
+
+        for _ in 0..3 {
+            assert_eq!(status(&tm, after_id), Some(TaskStatus::Waiting));
+            let round = run_pending(&mut tm, worker_id);
+            assert_eq!(round.len(), 2);
+            assert!(round.iter().all(|task| task.graph_id == "g"));
         }
+
+        assert!(tm.get_race(race_id).unwrap().is_finished());
+        assert_eq!(status(&tm, after_id), Some(TaskStatus::Pending));
+    }
+
+    fn graphs_of(tasks: &[Task]) -> Vec<&str> {
+        tasks.iter().map(|task| task.graph_id.as_str()).collect()
+    }
+
+    #[test]
+    fn paused_work_is_held_until_resumed() {
+        let mut tm = TaskManager::new(DistributionStrategy::Fifo);
+        tm.add_new_graph_tasks("a", 2, "{}");
+        tm.add_new_graph_tasks("b", 1, "{}");
+        let worker_id = Uuid::new_v4();
+
+        tm.pause_graph(DEFAULT_JOB_ID, "a");
+        assert_eq!(graphs_of(&tm.get_next_tasks(worker_id, 10)), vec!["b"]);
+        assert!(tm.get_next_task(worker_id).is_none());
+
+        tm.resume_graph(DEFAULT_JOB_ID, "a");
+        tm.pause_job(DEFAULT_JOB_ID);
+        assert!(tm.get_next_task(worker_id).is_none());
+        assert_eq!(
+            tm.count_tasks_with_status(DEFAULT_JOB_ID, TaskStatus::Pending),
+            2
+        );
+
+        tm.resume_job(DEFAULT_JOB_ID);
+        assert!(tm.get_paused_work().is_empty());
+        assert_eq!(graphs_of(&tm.get_next_tasks(worker_id, 10)), vec!["a", "a"]);
+    }
+
+    #[test]
+    fn abort_graph_drops_pending_and_cancels_assigned_tasks() {
+        let mut tm = TaskManager::new(DistributionStrategy::Fifo);
+        tm.add_new_graph_tasks("a", 3, "{}");
+        tm.add_new_graph_tasks("b", 2, "{}");
+        let worker_id = Uuid::new_v4();
+        let assigned = tm.get_next_task(worker_id).unwrap();
+        assert_eq!(assigned.graph_id, "a");
+
+        let record = tm.abort_graph(DEFAULT_JOB_ID, "a");
+        assert_eq!(record.graph_id.as_deref(), Some("a"));
+        assert_eq!(record.pending_dropped, 2);
+        assert_eq!(record.assigned_cancelled, 1);
+        assert_eq!(tm.get_aborts(DEFAULT_JOB_ID).len(), 1);
+
+        let progress = tm.get_graph_progress(DEFAULT_JOB_ID, "a");
+        assert_eq!(progress.aborted, 3);
+        assert!(progress.is_finished());
+        // Resultado de uma task cancelada chega tarde e é recusado.
+        assert!(
+            tm.complete_task(&result(&assigned, worker_id, 1.0))
+                .is_err()
+        );
+        assert_eq!(graphs_of(&run_pending(&mut tm, worker_id)), vec!["b", "b"]);
+    }
+
+    #[test]
+    fn abort_job_also_drops_waiting_stages() {
+        let mut tm = TaskManager::new(DistributionStrategy::Fifo);
+        tm.add_new_graph_tasks("a", 2, "{}");
+        tm.add_dependent_tasks(
+            DEFAULT_JOB_ID,
+            vec![Dependency::AllRunsOfGraph("a".to_string())],
+            vec![Task::new("after".to_string(), 0, "{}".to_string())],
+        );
+        tm.add_new_graph_tasks_with(
+            "other",
+            1,
+            "{}",
+            &GraphTaskOptions {
+                job_id: "outro".to_string(),
+                ..GraphTaskOptions::default()
+            },
+        );
+
+        let record = tm.abort_job(DEFAULT_JOB_ID);
+        assert_eq!(record.graph_id, None);
+        assert_eq!(record.pending_dropped, 3);
+        assert_eq!(
+            tm.count_tasks_with_status(DEFAULT_JOB_ID, TaskStatus::Aborted),
+            3
+        );
+        assert!(tm.get_graph_progress(DEFAULT_JOB_ID, "after").is_finished());
+
+        // Outros jobs seguem normalmente.
+        let worker_id = Uuid::new_v4();
+        assert_eq!(graphs_of(&run_pending(&mut tm, worker_id)), vec!["other"]);
+    }
+
+    /// Fonte de `count` tasks do graph que conta quantas já foram geradas.
+    fn counted_source(
+        graph_id: &'static str,
+        count: u32,
+    ) -> (
+        Arc<AtomicUsize>,
+        impl Iterator<Item = Task> + Send + 'static,
+    ) {
+        let pulled = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&pulled);
+        let tasks = (0..count).map(move |run_number| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Task::new(graph_id.to_string(), run_number, "{}".to_string())
+        });
+        (pulled, tasks)
+    }
+
+    #[test]
+    fn sources_are_consumed_only_up_to_the_buffer() {
+        let mut tm = TaskManager::new(DistributionStrategy::Fifo);
+        tm.set_source_buffer(3);
+        let (pulled, tasks) = counted_source("g", 10);
+        tm.add_task_source(DEFAULT_JOB_ID, tasks);
+        assert_eq!(pulled.load(Ordering::SeqCst), 3);
+        assert_eq!(tm.get_total_tasks(DEFAULT_JOB_ID), 3);
+
+        let worker_id = Uuid::new_v4();
+        let first = tm.get_next_task(worker_id).unwrap();
+        assert_eq!(pulled.load(Ordering::SeqCst), 4);
+        assert_eq!(
+            tm.count_tasks_with_status(DEFAULT_JOB_ID, TaskStatus::Pending),
+            3
+        );
+
+        tm.complete_task(&result(&first, worker_id, 0.0)).unwrap();
+        while !run_pending(&mut tm, worker_id).is_empty() {}
+        assert_eq!(pulled.load(Ordering::SeqCst), 10);
+        assert_eq!(tm.get_completed_tasks_count(DEFAULT_JOB_ID), 10);
+        assert!(!tm.has_pending_sources(DEFAULT_JOB_ID));
     }
 
-    pub fn get_total_tasks(&self) -> usize {
-        self.all_tasks_status.len()
+    #[test]
+    fn unassignable_source_tasks_are_bounded_by_the_overflow_limit() {
+        let mut tm = TaskManager::new(DistributionStrategy::Fifo);
+        tm.set_source_buffer(2);
+        tm.pause_graph(DEFAULT_JOB_ID, "pausado");
+        let (pulled, tasks) = counted_source("pausado", 100);
+        tm.add_task_source(DEFAULT_JOB_ID, tasks);
+        // Tasks pausadas não contam para o buffer, mas a fila não cresce
+        // além de `SOURCE_BUFFER_OVERFLOW` vezes o buffer.
+        assert_eq!(pulled.load(Ordering::SeqCst), 2 * SOURCE_BUFFER_OVERFLOW);
+
+        let worker_id = Uuid::new_v4();
+        assert!(tm.get_next_task(worker_id).is_none());
+        assert_eq!(pulled.load(Ordering::SeqCst), 2 * SOURCE_BUFFER_OVERFLOW);
+
+        tm.resume_graph(DEFAULT_JOB_ID, "pausado");
+        assert_eq!(tm.get_next_tasks(worker_id, 3).len(), 3);
+    }
+
+    #[test]
+    fn paused_jobs_do_not_pull_from_their_sources() {
+        let mut tm = TaskManager::new(DistributionStrategy::Fifo);
+        tm.set_source_buffer(2);
+        tm.pause_job(DEFAULT_JOB_ID);
+        let (pulled, tasks) = counted_source("g", 10);
+        tm.add_task_source(DEFAULT_JOB_ID, tasks);
+        assert_eq!(pulled.load(Ordering::SeqCst), 0);
+
+        tm.resume_job(DEFAULT_JOB_ID);
+        assert_eq!(pulled.load(Ordering::SeqCst), 2);
+    }
+
+    /// Conclui uma task no worker `original` e retorna a reexecução de
+    /// verificação atribuída ao worker `verifier`.
+    fn verify_on_other_worker(
+        tm: &mut TaskManager,
+        original: Uuid,
+        verifier: Uuid,
+    ) -> (Task, Task) {
+        tm.set_verification_rate(1.0);
+        tm.register_worker(original, WorkerCapabilities::default());
+        tm.register_worker(verifier, WorkerCapabilities::default());
+        tm.add_new_graph_tasks("g", 1, "{}");
+
+        let task = tm.get_next_task(original).unwrap();
+        tm.complete_task(&result(&task, original, 1.0)).unwrap();
+        // O worker que executou a task não pode verificá-la.
+        assert!(tm.get_next_task(original).is_none());
+        let verification = tm.get_next_task(verifier).unwrap();
+        assert_eq!(verification.verifies, Some(task.id));
+        assert_eq!(verification.seed, task.seed);
+        (task, verification)
+    }
+
+    #[test]
+    fn verification_reruns_on_another_worker() {
+        let mut tm = TaskManager::new(DistributionStrategy::Fifo);
+        let (original, verifier) = (Uuid::new_v4(), Uuid::new_v4());
+        let (task, verification) = verify_on_other_worker(&mut tm, original, verifier);
+
+        tm.complete_task(&result(&verification, verifier, 1.0))
+            .unwrap();
+        let records = tm.get_verifications(DEFAULT_JOB_ID);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].task_id, task.id);
+        assert_eq!(records[0].original_worker, original);
+        assert_eq!(records[0].verifying_worker, verifier);
+        assert!(records[0].is_match());
+        // A reexecução não conta como uma task a mais do job.
+        assert_eq!(tm.get_total_tasks(DEFAULT_JOB_ID), 1);
     }
 
-    pub fn get_completed_tasks_count(&self) -> usize {
-        self.all_tasks_status
-            .values()
-            .filter(|&&s| s == TaskStatus::Completed)
-            .count()
+    #[test]
+    fn verification_records_divergent_fitness() {
+        let mut tm = TaskManager::new(DistributionStrategy::Fifo);
+        let (original, verifier) = (Uuid::new_v4(), Uuid::new_v4());
+        let (_, verification) = verify_on_other_worker(&mut tm, original, verifier);
+
+        tm.complete_task(&result(&verification, verifier, 2.0))
+            .unwrap();
+        let records = tm.get_verifications(DEFAULT_JOB_ID);
+        assert!(!records[0].fitness_matches);
+        assert!(records[0].solution_matches);
+        assert_eq!(records[0].verifying_fitness, 2.0);
+    }
+
+    #[test]
+    fn verification_is_skipped_without_another_worker() {
+        let mut tm = TaskManager::new(DistributionStrategy::Fifo);
+        tm.set_verification_rate(1.0);
+        tm.add_new_graph_tasks("g", 1, "{}");
+        let worker_id = Uuid::new_v4();
+        run_pending(&mut tm, worker_id);
+
+        assert!(tm.get_next_task(Uuid::new_v4()).is_none());
+        assert!(tm.get_verifications(DEFAULT_JOB_ID).is_empty());
+    }
+
+    #[test]
+    fn expired_leases_return_to_the_queue() {
+        let mut tm = TaskManager::new(DistributionStrategy::Fifo);
+        tm.set_lease_timeout(Some(Duration::from_millis(20)));
+        tm.add_new_graph_tasks("g", 1, "{}");
+        let (slow, fast) = (Uuid::new_v4(), Uuid::new_v4());
+        let task = tm.get_next_task(slow).unwrap();
+        assert_eq!(tm.get_lease(task.id).unwrap().worker_id, slow);
+
+        std::thread::sleep(Duration::from_millis(40));
+        assert_eq!(tm.reclaim_expired_leases(), vec![task.id]);
+        assert_eq!(status(&tm, task.id), Some(TaskStatus::Pending));
+
+        let retried = tm.get_next_task(fast).unwrap();
+        assert_eq!(retried.id, task.id);
+        // Só quem detém o lease conclui a task.
+        assert!(tm.complete_task(&result(&task, slow, 1.0)).is_err());
+        tm.complete_task(&result(&retried, fast, 1.0)).unwrap();
+        assert_eq!(status(&tm, task.id), Some(TaskStatus::Completed));
+    }
+
+    #[test]
+    fn renewed_leases_do_not_expire() {
+        let mut tm = TaskManager::new(DistributionStrategy::Fifo);
+        tm.set_lease_timeout(Some(Duration::from_millis(100)));
+        tm.add_new_graph_tasks("g", 2, "{}");
+        let worker_id = Uuid::new_v4();
+        let tasks = tm.get_next_tasks(worker_id, 2);
+        assert_eq!(tasks.len(), 2);
+
+        for _ in 0..3 {
+            std::thread::sleep(Duration::from_millis(60));
+            tm.renew_leases(worker_id);
+        }
+        assert!(tm.reclaim_expired_leases().is_empty());
+        // Renovar os leases de outro worker não afeta os deste.
+        tm.renew_leases(Uuid::new_v4());
+        std::thread::sleep(Duration::from_millis(120));
+        assert_eq!(tm.reclaim_expired_leases().len(), 2);
     }
 
-    pub fn get_tasks_status(&self) -> &HashMap<Uuid, TaskStatus> {
-        &self.all_tasks_status
+    #[test]
+    fn leases_without_timeout_never_expire() {
+        let mut tm = TaskManager::new(DistributionStrategy::Fifo);
+        tm.add_new_graph_tasks("g", 1, "{}");
+        let task = tm.get_next_task(Uuid::new_v4()).unwrap();
+        assert!(tm.get_lease(task.id).unwrap().expires_at.is_none());
+        assert!(tm.reclaim_expired_leases().is_empty());
     }
 }
@@ -0,0 +1,30 @@
+use std::collections::HashSet;
+
+use crate::common::Task;
+
+/// Gera as tasks de um job sob demanda, conforme a fila esvazia.
+pub type TaskIterator = Box<dyn Iterator<Item = Task> + Send>;
+
+pub(crate) struct TaskSource {
+    pub job_id: String,
+    pub tasks: TaskIterator,
+    /// Graphs abortados depois do registro; suas tasks são descartadas sem
+    /// serem materializadas.
+    pub skipped_graphs: HashSet<String>,
+}
+
+impl TaskSource {
+    pub fn new(job_id: &str, tasks: TaskIterator) -> Self {
+        Self {
+            job_id: job_id.to_string(),
+            tasks,
+            skipped_graphs: HashSet::new(),
+        }
+    }
+
+    pub fn next_task(&mut self) -> Option<Task> {
+        let skipped_graphs = &self.skipped_graphs;
+        self.tasks
+            .find(|task| !skipped_graphs.contains(&task.graph_id))
+    }
+}
@@ -0,0 +1,50 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::common::{Task, TaskResult};
+
+/// Comparação entre a execução original de uma task e a reexecução feita por
+/// outro worker.
+#[derive(Debug, Clone, Serialize)]
+pub struct VerificationRecord {
+    pub task_id: Uuid,
+    pub graph_id: String,
+    pub run_number: u32,
+    pub seed: u64,
+    pub original_worker: Uuid,
+    pub verifying_worker: Uuid,
+    pub original_fitness: f64,
+    pub verifying_fitness: f64,
+    pub fitness_matches: bool,
+    pub solution_matches: bool,
+}
+
+impl VerificationRecord {
+    /// Cada execução vem acompanhada do worker que detinha o lease, não do
+    /// `worker_id` informado no próprio resultado.
+    pub(crate) fn compare(
+        task: &Task,
+        original_worker: Uuid,
+        original: &TaskResult,
+        verifying_worker: Uuid,
+        verifying: &TaskResult,
+    ) -> Self {
+        Self {
+            task_id: original.task_id,
+            graph_id: task.graph_id.clone(),
+            run_number: task.run_number,
+            seed: task.seed,
+            original_worker,
+            verifying_worker,
+            original_fitness: original.fitness,
+            verifying_fitness: verifying.fitness,
+            fitness_matches: original.fitness.to_bits() == verifying.fitness.to_bits(),
+            solution_matches: original.solution_data == verifying.solution_data,
+        }
+    }
+
+    #[must_use]
+    pub const fn is_match(&self) -> bool {
+        self.fitness_matches && self.solution_matches
+    }
+}
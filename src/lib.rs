@@ -1,5 +1,6 @@
 pub mod common;
 pub mod host;
+pub mod transport;
 pub mod utils;
 pub mod worker;
 
@@ -0,0 +1,57 @@
+use std::io;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use super::{Connection, Listener};
+
+/// Aceita workers de dois listeners ao mesmo tempo, por exemplo TCP e WebSocket,
+/// para que todos sejam atendidos pelo mesmo `TaskManager`.
+///
+/// Ambos os `accept` precisam ser seguros para cancelamento.
+pub struct EitherListener<A, B> {
+    left: A,
+    right: B,
+}
+
+impl<A: Listener, B: Listener> EitherListener<A, B> {
+    pub const fn new(left: A, right: B) -> Self {
+        Self { left, right }
+    }
+}
+
+pub enum EitherConnection<A, B> {
+    Left(A),
+    Right(B),
+}
+
+impl<A: Listener, B: Listener> Listener for EitherListener<A, B> {
+    type Connection = EitherConnection<A::Connection, B::Connection>;
+
+    async fn accept(&mut self) -> io::Result<(Self::Connection, String)> {
+        tokio::select! {
+            accepted = self.left.accept() => {
+                accepted.map(|(connection, peer)| (EitherConnection::Left(connection), peer))
+            }
+            accepted = self.right.accept() => {
+                accepted.map(|(connection, peer)| (EitherConnection::Right(connection), peer))
+            }
+        }
+    }
+}
+
+impl<A: Connection, B: Connection> Connection for EitherConnection<A, B> {
+    async fn send<M: Serialize + Sync>(&mut self, message: &M) -> io::Result<()> {
+        match self {
+            Self::Left(connection) => connection.send(message).await,
+            Self::Right(connection) => connection.send(message).await,
+        }
+    }
+
+    async fn recv<M: DeserializeOwned>(&mut self) -> io::Result<Option<M>> {
+        match self {
+            Self::Left(connection) => connection.recv().await,
+            Self::Right(connection) => connection.recv().await,
+        }
+    }
+}
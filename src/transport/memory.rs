@@ -0,0 +1,73 @@
+use std::fmt;
+use std::io;
+
+use tokio::io::DuplexStream;
+use tokio::sync::mpsc;
+
+use super::{Connector, LineConnection, Listener};
+
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// Cria um transporte em memória para rodar host e workers no mesmo processo.
+///
+/// O `MemoryConnector` pode ser clonado livremente, um por worker.
+#[must_use]
+pub fn channel() -> (MemoryListener, MemoryConnector) {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    (
+        MemoryListener {
+            receiver,
+            accepted: 0,
+        },
+        MemoryConnector { sender },
+    )
+}
+
+pub struct MemoryListener {
+    receiver: mpsc::UnboundedReceiver<DuplexStream>,
+    accepted: usize,
+}
+
+impl Listener for MemoryListener {
+    type Connection = LineConnection<DuplexStream>;
+
+    async fn accept(&mut self) -> io::Result<(Self::Connection, String)> {
+        let stream = self.receiver.recv().await.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "Todos os conectores em memória foram descartados",
+            )
+        })?;
+        self.accepted += 1;
+        Ok((
+            LineConnection::new(stream),
+            format!("memory:{}", self.accepted),
+        ))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MemoryConnector {
+    sender: mpsc::UnboundedSender<DuplexStream>,
+}
+
+impl Connector for MemoryConnector {
+    type Connection = LineConnection<DuplexStream>;
+
+    async fn connect(&self) -> io::Result<Self::Connection> {
+        let (local, remote) = tokio::io::duplex(BUFFER_SIZE);
+        self.sender.send(remote).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::ConnectionRefused,
+                "Listener em memória não está mais ativo",
+            )
+        })?;
+        Ok(LineConnection::new(local))
+    }
+}
+
+impl fmt::Display for MemoryConnector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("memory")
+    }
+}
@@ -0,0 +1,90 @@
+mod either;
+mod memory;
+mod tcp;
+#[cfg(unix)]
+mod unix;
+#[cfg(feature = "websocket")]
+mod websocket;
+
+use std::future::Future;
+use std::io;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+pub use either::{EitherConnection, EitherListener};
+pub use memory::{MemoryConnector, MemoryListener, channel};
+pub use tcp::TcpConnector;
+#[cfg(unix)]
+pub use unix::UnixConnector;
+#[cfg(feature = "websocket")]
+pub use websocket::{WebSocketConnection, WebSocketConnector, WebSocketListener};
+
+/// Canal bidirecional de mensagens entre host e worker.
+pub trait Connection: Send {
+    fn send<M: Serialize + Sync>(
+        &mut self,
+        message: &M,
+    ) -> impl Future<Output = io::Result<()>> + Send;
+
+    /// Retorna `Ok(None)` quando o outro lado encerra a conexão.
+    fn recv<M: DeserializeOwned>(&mut self) -> impl Future<Output = io::Result<Option<M>>> + Send;
+}
+
+/// Lado do host: aceita conexões de workers.
+pub trait Listener: Send + 'static {
+    type Connection: Connection + 'static;
+
+    /// Retorna a conexão aceita e uma descrição do par remoto para os logs.
+    /// Um erro `BrokenPipe` indica que o listener não aceitará mais conexões;
+    /// os demais erros são tratados como transitórios.
+    fn accept(&mut self) -> impl Future<Output = io::Result<(Self::Connection, String)>> + Send;
+}
+
+/// Lado do worker: abre conexões com o host.
+pub trait Connector: std::fmt::Display + Send + Sync + 'static {
+    type Connection: Connection;
+
+    fn connect(&self) -> impl Future<Output = io::Result<Self::Connection>> + Send;
+}
+
+/// Conexão sobre um fluxo de bytes com uma mensagem JSON por linha.
+pub struct LineConnection<S> {
+    stream: BufReader<S>,
+    buffer: String,
+}
+
+impl<S> LineConnection<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream: BufReader::new(stream),
+            buffer: String::new(),
+        }
+    }
+}
+
+impl<S> Connection for LineConnection<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    async fn send<M: Serialize + Sync>(&mut self, message: &M) -> io::Result<()> {
+        let encoded = serde_json::to_vec(message)?;
+        self.stream.write_all(&encoded).await?;
+        self.stream.write_all(b"\n").await?; // Adiciona delimitador de newline
+        self.stream.flush().await
+    }
+
+    async fn recv<M: DeserializeOwned>(&mut self) -> io::Result<Option<M>> {
+        self.buffer.clear();
+
+        if self.stream.read_line(&mut self.buffer).await? == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(serde_json::from_str(&self.buffer)?))
+    }
+}
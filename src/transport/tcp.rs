@@ -0,0 +1,41 @@
+use std::fmt;
+use std::io;
+
+use tokio::net::{TcpListener, TcpStream};
+
+use super::{Connector, LineConnection, Listener};
+
+impl Listener for TcpListener {
+    type Connection = LineConnection<TcpStream>;
+
+    async fn accept(&mut self) -> io::Result<(Self::Connection, String)> {
+        let (socket, remote_addr) = TcpListener::accept(self).await?;
+        Ok((LineConnection::new(socket), remote_addr.to_string()))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TcpConnector {
+    addr: String,
+}
+
+impl TcpConnector {
+    pub fn new(addr: impl Into<String>) -> Self {
+        Self { addr: addr.into() }
+    }
+}
+
+impl Connector for TcpConnector {
+    type Connection = LineConnection<TcpStream>;
+
+    async fn connect(&self) -> io::Result<Self::Connection> {
+        let stream = TcpStream::connect(&self.addr).await?;
+        Ok(LineConnection::new(stream))
+    }
+}
+
+impl fmt::Display for TcpConnector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "tcp://{}", self.addr)
+    }
+}
@@ -0,0 +1,46 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+use tokio::net::{UnixListener, UnixStream};
+
+use super::{Connector, LineConnection, Listener};
+
+impl Listener for UnixListener {
+    type Connection = LineConnection<UnixStream>;
+
+    async fn accept(&mut self) -> io::Result<(Self::Connection, String)> {
+        let (socket, remote_addr) = UnixListener::accept(self).await?;
+        let peer = remote_addr.as_pathname().map_or_else(
+            || "unix:<anônimo>".to_string(),
+            |path| format!("unix:{}", path.display()),
+        );
+        Ok((LineConnection::new(socket), peer))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UnixConnector {
+    path: PathBuf,
+}
+
+impl UnixConnector {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Connector for UnixConnector {
+    type Connection = LineConnection<UnixStream>;
+
+    async fn connect(&self) -> io::Result<Self::Connection> {
+        let stream = UnixStream::connect(&self.path).await?;
+        Ok(LineConnection::new(stream))
+    }
+}
+
+impl fmt::Display for UnixConnector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unix:{}", self.path.display())
+    }
+}
@@ -0,0 +1,239 @@
+use std::fmt;
+use std::io;
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use log::{debug, warn};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+use super::{Connection, Connector, Listener};
+
+const PENDING_HANDSHAKES: usize = 64;
+/// Pausa após uma falha de `accept` (ex.: limite de descritores), para não
+/// girar em falso enquanto o erro persiste.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Conexão que transporta cada mensagem JSON em um frame de texto WebSocket.
+pub struct WebSocketConnection<S> {
+    stream: WebSocketStream<S>,
+}
+
+impl<S> Connection for WebSocketConnection<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    async fn send<M: Serialize + Sync>(&mut self, message: &M) -> io::Result<()> {
+        let encoded = serde_json::to_string(message)?;
+        self.stream
+            .send(Message::text(encoded))
+            .await
+            .map_err(io::Error::other)
+    }
+
+    async fn recv<M: DeserializeOwned>(&mut self) -> io::Result<Option<M>> {
+        while let Some(frame) = self.stream.next().await {
+            match frame.map_err(io::Error::other)? {
+                Message::Text(text) => return Ok(Some(serde_json::from_str(&text)?)),
+                Message::Binary(data) => return Ok(Some(serde_json::from_slice(&data)?)),
+                Message::Close(_) => return Ok(None),
+                Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => {}
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Listener WebSocket do host. Os handshakes rodam em segundo plano, então um
+/// cliente lento não bloqueia os demais e `accept` é seguro para cancelamento.
+pub struct WebSocketListener {
+    receiver: mpsc::Receiver<(WebSocketStream<TcpStream>, String)>,
+}
+
+impl WebSocketListener {
+    pub async fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let (sender, receiver) = mpsc::channel(PENDING_HANDSHAKES);
+
+        tokio::spawn(async move {
+            loop {
+                let (socket, remote_addr) = match listener.accept().await {
+                    Ok(accepted) => accepted,
+                    Err(e) => {
+                        warn!("Falha ao aceitar conexão WebSocket: {e}");
+                        tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+                        continue;
+                    }
+                };
+
+                if sender.is_closed() {
+                    debug!("Listener WebSocket descartado, encerrando aceitação.");
+                    return;
+                }
+
+                let handshake_sender = sender.clone();
+                tokio::spawn(async move {
+                    match tokio_tungstenite::accept_async(socket).await {
+                        Ok(stream) => {
+                            let peer = format!("ws://{remote_addr}");
+                            let _ = handshake_sender.send((stream, peer)).await;
+                        }
+                        Err(e) => warn!("Handshake WebSocket com {remote_addr} falhou: {e}"),
+                    }
+                });
+            }
+        });
+
+        Ok(Self { receiver })
+    }
+}
+
+impl Listener for WebSocketListener {
+    type Connection = WebSocketConnection<TcpStream>;
+
+    async fn accept(&mut self) -> io::Result<(Self::Connection, String)> {
+        let (stream, peer) = self.receiver.recv().await.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "Listener WebSocket não está mais ativo",
+            )
+        })?;
+        Ok((WebSocketConnection { stream }, peer))
+    }
+}
+
+/// Conector WebSocket do worker, opcionalmente através de um proxy HTTP
+/// (túnel via `CONNECT`).
+#[derive(Debug, Clone)]
+pub struct WebSocketConnector {
+    url: String,
+    proxy_addr: Option<String>,
+}
+
+impl WebSocketConnector {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            proxy_addr: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_http_proxy(mut self, proxy_addr: impl Into<String>) -> Self {
+        self.proxy_addr = Some(proxy_addr.into());
+        self
+    }
+}
+
+impl Connector for WebSocketConnector {
+    type Connection = WebSocketConnection<MaybeTlsStream<TcpStream>>;
+
+    async fn connect(&self) -> io::Result<Self::Connection> {
+        let request = self
+            .url
+            .as_str()
+            .into_client_request()
+            .map_err(io::Error::other)?;
+
+        let stream = match &self.proxy_addr {
+            Some(proxy_addr) => {
+                // O túnel é TCP puro; sem TLS sobre ele o tráfego de um `wss`
+                // seguiria em texto claro.
+                if request.uri().scheme_str() == Some("wss") {
+                    return Err(io::Error::new(
+                        io::ErrorKind::Unsupported,
+                        "wss:// através de proxy HTTP não é suportado",
+                    ));
+                }
+                let authority = request_authority(&request)?;
+                let tunnel = connect_through_proxy(proxy_addr, &authority).await?;
+                let (stream, _) =
+                    tokio_tungstenite::client_async(request, MaybeTlsStream::Plain(tunnel))
+                        .await
+                        .map_err(io::Error::other)?;
+                stream
+            }
+            None => {
+                let (stream, _) = tokio_tungstenite::connect_async(request)
+                    .await
+                    .map_err(io::Error::other)?;
+                stream
+            }
+        };
+
+        Ok(WebSocketConnection { stream })
+    }
+}
+
+impl fmt::Display for WebSocketConnector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.proxy_addr {
+            Some(proxy_addr) => write!(f, "{} (via proxy {proxy_addr})", self.url),
+            None => f.write_str(&self.url),
+        }
+    }
+}
+
+fn request_authority(
+    request: &tokio_tungstenite::tungstenite::handshake::client::Request,
+) -> io::Result<String> {
+    let uri = request.uri();
+    let host = uri
+        .host()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "URL WebSocket sem host"))?;
+    let port = uri
+        .port_u16()
+        .unwrap_or(if uri.scheme_str() == Some("wss") {
+            443
+        } else {
+            80
+        });
+    Ok(format!("{host}:{port}"))
+}
+
+async fn connect_through_proxy(proxy_addr: &str, authority: &str) -> io::Result<TcpStream> {
+    let mut stream = BufReader::new(TcpStream::connect(proxy_addr).await?);
+    let connect_request = format!("CONNECT {authority} HTTP/1.1\r\nHost: {authority}\r\n\r\n");
+    stream.write_all(connect_request.as_bytes()).await?;
+    stream.flush().await?;
+
+    let mut status_line = String::new();
+    stream.read_line(&mut status_line).await?;
+    let accepted = status_line
+        .split_whitespace()
+        .nth(1)
+        .is_some_and(|code| code.starts_with('2'));
+    if !accepted {
+        return Err(io::Error::new(
+            io::ErrorKind::ConnectionRefused,
+            format!(
+                "Proxy recusou o túnel para {authority}: {}",
+                status_line.trim()
+            ),
+        ));
+    }
+
+    let mut header = String::new();
+    loop {
+        header.clear();
+        if stream.read_line(&mut header).await? == 0 || header == "\r\n" || header == "\n" {
+            break;
+        }
+    }
+
+    if !stream.buffer().is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Proxy enviou dados inesperados após estabelecer o túnel",
+        ));
+    }
+
+    Ok(stream.into_inner())
+}
@@ -1,28 +1,83 @@
 use log::{debug, error, info};
-use serde_json;
 use std::error::Error;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::net::TcpStream;
-use tokio::time::sleep;
+use tokio::time::{Instant, interval_at, sleep};
 use uuid::Uuid;
 
-use crate::common::{GARunner, Request, Response};
+use crate::common::{
+    ClusterSecret, ConfigPayload, GARunner, PayloadError, ProgressHandle, ProgressSample, Request,
+    Response, SolutionPayload, Task, TaskResult, WorkerCapabilities, progress_channel,
+};
+use crate::transport::{Connection, Connector, TcpConnector};
 
-pub async fn start_worker<T: GARunner>(
+/// Amostras de progresso que podem esperar o envio ao host; além disso só a
+/// mais recente é mantida.
+const PROGRESS_BUFFER: usize = 256;
+
+#[derive(Debug, Clone)]
+pub struct WorkerOptions {
+    pub secret: Option<ClusterSecret>,
+    /// Recursos anunciados ao host, usados para casar com os requisitos das tasks.
+    pub capabilities: WorkerCapabilities,
+    /// Quantidade máxima de tasks pedidas e reportadas por mensagem.
+    pub batch_size: u32,
+    /// Tempo que o host pode segurar um pedido de task quando a fila está vazia.
+    pub task_wait_timeout: Duration,
+    /// Intervalo dos heartbeats enviados enquanto o lote executa, renovando os
+    /// leases no host. Deve ser menor que o lease configurado no host.
+    pub heartbeat_interval: Option<Duration>,
+}
+
+impl Default for WorkerOptions {
+    fn default() -> Self {
+        Self {
+            secret: None,
+            capabilities: WorkerCapabilities::detect(),
+            batch_size: 1,
+            task_wait_timeout: Duration::from_secs(30),
+            heartbeat_interval: Some(Duration::from_secs(10)),
+        }
+    }
+}
+
+/// Conecta ao host e executa tasks indefinidamente. Os tipos de configuração
+/// e solução `Cfg` e `Sol` são inferidos a partir do `GARunner`.
+pub async fn start_worker<T, Cfg, Sol>(
     host_addr: &str,
     worker_id: Uuid,
     ga_runner: Arc<T>,
-) -> Result<(), Box<dyn Error>> {
-    info!("Trabalhador {worker_id} tentando se conectar ao host em {host_addr}");
+    options: WorkerOptions,
+) -> Result<(), Box<dyn Error>>
+where
+    T: GARunner<Cfg, Sol>,
+    Cfg: ConfigPayload,
+    Sol: SolutionPayload,
+{
+    run_worker(TcpConnector::new(host_addr), worker_id, ga_runner, options).await
+}
+
+pub async fn run_worker<C, T, Cfg, Sol>(
+    connector: C,
+    worker_id: Uuid,
+    ga_runner: Arc<T>,
+    options: WorkerOptions,
+) -> Result<(), Box<dyn Error>>
+where
+    C: Connector,
+    T: GARunner<Cfg, Sol>,
+    Cfg: ConfigPayload,
+    Sol: SolutionPayload,
+{
+    info!("Trabalhador {worker_id} tentando se conectar ao host em {connector}");
 
     loop {
-        match TcpStream::connect(host_addr).await {
-            Ok(stream) => {
+        match connector.connect().await {
+            Ok(connection) => {
                 info!("Trabalhador {worker_id} conectado ao host.");
                 if let Err(e) =
-                    handle_host_connection(stream, worker_id, Arc::clone(&ga_runner)).await
+                    handle_host_connection(connection, worker_id, Arc::clone(&ga_runner), &options)
+                        .await
                 {
                     error!("Conexão com o host perdida ou erro: {e}");
                 }
@@ -37,61 +92,56 @@ pub async fn start_worker<T: GARunner>(
     }
 }
 
-async fn handle_host_connection<T: GARunner>(
-    stream: TcpStream,
+async fn handle_host_connection<C, T, Cfg, Sol>(
+    mut connection: C,
     worker_id: Uuid,
     ga_runner: Arc<T>,
-) -> Result<(), Box<dyn Error>> {
-    let mut reader = BufReader::new(stream);
-    let mut line = String::new();
+    options: &WorkerOptions,
+) -> Result<(), Box<dyn Error>>
+where
+    C: Connection,
+    T: GARunner<Cfg, Sol>,
+    Cfg: ConfigPayload,
+    Sol: SolutionPayload,
+{
+    authenticate(&mut connection, worker_id, options).await?;
 
     loop {
-        line.clear();
+        let request = Request::RequestTasks {
+            worker_id,
+            max_tasks: options.batch_size.max(1),
+            wait_ms: Some(options.task_wait_timeout.as_millis() as u64),
+        };
+        connection.send(&request).await?;
+        debug!(
+            "Trabalhador {worker_id} solicitou até {} tarefas.",
+            options.batch_size
+        );
 
-        let request = Request::RequestTask { worker_id };
-        let encoded_request = serde_json::to_vec(&request)?;
-        reader.write_all(&encoded_request).await?;
-        reader.write_all(b"\n").await?;
-        reader.flush().await?;
-        debug!("Trabalhador {worker_id} solicitou uma tarefa.");
-
-        let bytes_read = reader.read_line(&mut line).await?;
-
-        if bytes_read == 0 {
+        let Some(response) = connection.recv::<Response>().await? else {
             return Err("Host desconectado.".into());
-        }
-
-        let response: Response = serde_json::from_str(&line)?;
+        };
         debug!("Trabalhador {worker_id} recebeu a resposta: {response:?}");
 
-        match response {
-            Response::AssignTask { task } => {
-                info!("Trabalhador {} recebeu a tarefa {}", worker_id, task.id);
-                let result = ga_runner.run(task, worker_id);
-                info!(
-                    "Trabalhador {} terminou a tarefa {}. Melhor fitness: {}",
-                    worker_id, result.task_id, result.fitness
-                );
-
-                let clone_result = result.clone();
-                let report_request = Request::ReportResult { worker_id, result };
-                let encoded_report = serde_json::to_vec(&report_request)?;
-                reader.write_all(&encoded_report).await?;
-                reader.write_all(b"\n").await?;
-                reader.flush().await?;
-                debug!(
-                    "Trabalhador {} reportou o resultado da tarefa {}",
-                    worker_id, clone_result.task_id
-                );
-            }
+        let tasks = match response {
+            Response::AssignTask { task } => vec![*task],
+            Response::AssignTasks { tasks } => tasks,
             Response::NoTaskAvailable => {
+                // O host já segurou a requisição até o timeout, então pedimos de novo.
                 info!(
                     "Trabalhador {worker_id} recebeu NoTaskAvailable. Aguardando novas tarefas..."
                 );
-                sleep(Duration::from_secs(2)).await;
+                continue;
             }
             Response::Ack => {
                 debug!("Trabalhador {worker_id} recebeu Ack.");
+                continue;
+            }
+            Response::Challenge { .. } => {
+                return Err("Host enviou um desafio de autenticação inesperado.".into());
+            }
+            Response::Unauthorized { reason } => {
+                return Err(format!("Host rejeitou o trabalhador: {reason}").into());
             }
             Response::Command {
                 command_type,
@@ -100,6 +150,190 @@ async fn handle_host_connection<T: GARunner>(
                 info!(
                     "Trabalhador {worker_id} recebeu comando: {command_type} com payload {payload}"
                 );
+                continue;
+            }
+        };
+
+        // Um heartbeat renova os leases de todo o lote, inclusive das tasks que
+        // ainda não começaram.
+        let mut heartbeat = options
+            .heartbeat_interval
+            .map(|period| interval_at(Instant::now() + period, period));
+
+        let mut results = Vec::with_capacity(tasks.len());
+        for task in tasks {
+            info!("Trabalhador {} recebeu a tarefa {}", worker_id, task.id);
+            let task_id = task.id;
+            let (progress, mut progress_rx) = progress_channel(task_id, PROGRESS_BUFFER);
+            let runner = Arc::clone(&ga_runner);
+            let mut running = tokio::task::spawn_blocking(move || {
+                run_typed(runner.as_ref(), task, worker_id, &progress)
+            });
+
+            // Encaminha o progresso enquanto o AG executa.
+            let outcome = loop {
+                tokio::select! {
+                    outcome = &mut running => break outcome,
+                    Some(sample) = progress_rx.recv() => {
+                        let mut samples = vec![sample];
+                        samples.extend(progress_rx.drain());
+                        report_progress(&mut connection, worker_id, task_id, samples).await?;
+                    }
+                    _ = async { heartbeat.as_mut().expect("heartbeat ativo").tick().await },
+                        if heartbeat.is_some() =>
+                    {
+                        debug!("Trabalhador {worker_id} enviando heartbeat");
+                        send_expecting_ack(&mut connection, &Request::Heartbeat { worker_id })
+                            .await?;
+                    }
+                }
+            };
+            let remaining = progress_rx.drain();
+            if !remaining.is_empty() {
+                report_progress(&mut connection, worker_id, task_id, remaining).await?;
+            }
+
+            let reason = match outcome {
+                Ok(Ok(result)) => {
+                    info!(
+                        "Trabalhador {} terminou a tarefa {}. Melhor fitness: {}",
+                        worker_id, result.task_id, result.fitness
+                    );
+                    results.push(result);
+                    continue;
+                }
+                Ok(Err(e)) => e.to_string(),
+                Err(e) => format!("runner falhou: {e}"),
+            };
+            error!("Trabalhador {worker_id} não conseguiu executar a tarefa {task_id}: {reason}");
+            let failure = Request::ReportFailure {
+                worker_id,
+                task_id,
+                reason,
+            };
+            send_expecting_ack(&mut connection, &failure).await?;
+        }
+
+        let reported = results.len();
+        let report_request = Request::ReportResults { worker_id, results };
+        connection.send(&report_request).await?;
+        debug!("Trabalhador {worker_id} reportou {reported} resultados");
+
+        // Aguarda a confirmação antes de pedir outra task, senão a próxima
+        // requisição pode ficar presa atrás de um long-poll no host.
+        match connection.recv::<Response>().await? {
+            Some(Response::Ack) => {}
+            Some(Response::Unauthorized { reason }) => {
+                return Err(format!("Host rejeitou o resultado: {reason}").into());
+            }
+            Some(other) => {
+                return Err(format!("Resposta inesperada ao reportar: {other:?}").into());
+            }
+            None => return Err("Host desconectado.".into()),
+        }
+    }
+}
+
+/// Converte a task para os tipos do runner, executa e converte o resultado de
+/// volta para a forma enviada ao host.
+pub(crate) fn run_typed<T, Cfg, Sol>(
+    ga_runner: &T,
+    task: Task,
+    worker_id: Uuid,
+    progress: &ProgressHandle,
+) -> Result<TaskResult, PayloadError>
+where
+    T: GARunner<Cfg, Sol>,
+    Cfg: ConfigPayload,
+    Sol: SolutionPayload,
+{
+    let seed = task.seed;
+    let task = task
+        .into_typed::<Cfg>()
+        .map_err(|e| format!("configuração inválida: {e}"))?;
+    let mut result = ga_runner
+        .run_with_progress(task, worker_id, progress)
+        .into_untyped()
+        .map_err(|e| format!("falha ao serializar a solução: {e}"))?;
+    result.seed = seed;
+    Ok(result)
+}
+
+async fn report_progress<C: Connection>(
+    connection: &mut C,
+    worker_id: Uuid,
+    task_id: Uuid,
+    samples: Vec<ProgressSample>,
+) -> Result<(), Box<dyn Error>> {
+    let reported = samples.len();
+    let request = Request::ReportProgress {
+        worker_id,
+        task_id,
+        samples,
+    };
+    send_expecting_ack(connection, &request).await?;
+    debug!("Trabalhador {worker_id} reportou {reported} amostras de progresso da tarefa {task_id}");
+    Ok(())
+}
+
+/// Envia uma requisição que o host confirma com `Ack`.
+async fn send_expecting_ack<C: Connection>(
+    connection: &mut C,
+    request: &Request,
+) -> Result<(), Box<dyn Error>> {
+    connection.send(request).await?;
+
+    match connection.recv::<Response>().await? {
+        Some(Response::Ack) => Ok(()),
+        Some(Response::Unauthorized { reason }) => {
+            Err(format!("Host rejeitou a requisição: {reason}").into())
+        }
+        Some(other) => Err(format!("Resposta inesperada do host: {other:?}").into()),
+        None => Err("Host desconectado.".into()),
+    }
+}
+
+async fn authenticate<C: Connection>(
+    connection: &mut C,
+    worker_id: Uuid,
+    options: &WorkerOptions,
+) -> Result<(), Box<dyn Error>> {
+    let mut request = Request::Hello {
+        worker_id,
+        capabilities: options.capabilities.clone(),
+    };
+
+    loop {
+        connection.send(&request).await?;
+
+        let Some(response) = connection.recv::<Response>().await? else {
+            return Err("Host desconectado durante a autenticação.".into());
+        };
+
+        match response {
+            Response::Ack => {
+                info!("Trabalhador {worker_id} aceito pelo host.");
+                return Ok(());
+            }
+            Response::Challenge { nonce } => {
+                let Some(secret) = &options.secret else {
+                    return Err(
+                        "Host exige autenticação, mas nenhum segredo foi configurado.".into(),
+                    );
+                };
+                debug!("Trabalhador {worker_id} respondendo ao desafio de autenticação.");
+                request = Request::Authenticate {
+                    worker_id,
+                    signature: secret.sign(&nonce, worker_id),
+                };
+            }
+            Response::Unauthorized { reason } => {
+                return Err(format!("Autenticação recusada pelo host: {reason}").into());
+            }
+            other => {
+                return Err(
+                    format!("Resposta inesperada durante a autenticação: {other:?}").into(),
+                );
             }
         }
     }
@@ -0,0 +1,173 @@
+use std::{collections::HashMap, error::Error, fs};
+
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::common::{
+    ConfigPayload, GARunner, Objective, ProgressHandle, SolutionPayload, Task, TaskResult,
+};
+use crate::host::result_aggregator::ReportedResult;
+use crate::worker::client::run_typed;
+
+#[derive(Deserialize)]
+struct SavedReport {
+    job_id: String,
+    graphs: HashMap<String, SavedGraph>,
+}
+
+#[derive(Deserialize)]
+struct SavedGraph {
+    #[serde(default)]
+    objective: Objective,
+    results: Vec<ReportedResult>,
+}
+
+/// Comparação entre o resultado gravado no relatório e a reexecução local.
+#[derive(Debug, Clone)]
+pub struct ReplayOutcome {
+    pub task: Task,
+    pub recorded: TaskResult,
+    pub replayed: TaskResult,
+}
+
+impl ReplayOutcome {
+    #[must_use]
+    pub fn fitness_matches(&self) -> bool {
+        self.recorded.fitness.to_bits() == self.replayed.fitness.to_bits()
+    }
+
+    #[must_use]
+    pub fn solution_matches(&self) -> bool {
+        self.recorded.solution_data == self.replayed.solution_data
+    }
+
+    #[must_use]
+    pub fn is_reproduced(&self) -> bool {
+        self.fitness_matches() && self.solution_matches()
+    }
+}
+
+/// Reconstrói a task (graph, execução, configuração, semente, objetivo e
+/// parâmetros) de um relatório salvo por `ResultAggregator::generate_and_save_report`, junto com
+/// o resultado gravado.
+pub fn load_task_from_report(
+    report_path: &str,
+    task_id: Uuid,
+) -> Result<(Task, TaskResult), Box<dyn Error>> {
+    let report: SavedReport = serde_json::from_str(&fs::read_to_string(report_path)?)?;
+
+    let (graph_objective, reported) = report
+        .graphs
+        .into_values()
+        .flat_map(|graph| {
+            let objective = graph.objective;
+            graph
+                .results
+                .into_iter()
+                .map(move |reported| (objective, reported))
+        })
+        .find(|(_, reported)| reported.result.task_id == task_id)
+        .ok_or_else(|| format!("Task {task_id} não encontrada em {report_path}"))?;
+
+    let (Some(run_number), Some(ag_config)) = (reported.run_number, reported.ag_config) else {
+        return Err(format!(
+            "O relatório não guarda a configuração da task {task_id}, não é possível reexecutá-la"
+        )
+        .into());
+    };
+
+    let recorded = reported.result;
+    let mut task = Task::new(recorded.graph_id.clone(), run_number, ag_config);
+    task.id = task_id;
+    task.job_id = report.job_id;
+    task.seed = recorded.seed;
+    // Relatórios antigos só guardam o objetivo do graph.
+    task.objective = reported.objective.unwrap_or(graph_objective);
+    task.objective_directions = reported.objective_directions;
+    task.parameters = reported.parameters;
+    Ok((task, recorded))
+}
+
+/// Reexecuta localmente uma task do relatório e compara com o resultado
+/// gravado.
+pub fn replay_task<T, Cfg, Sol>(
+    runner: &T,
+    report_path: &str,
+    task_id: Uuid,
+) -> Result<ReplayOutcome, Box<dyn Error>>
+where
+    T: GARunner<Cfg, Sol>,
+    Cfg: ConfigPayload,
+    Sol: SolutionPayload,
+{
+    let (task, recorded) = load_task_from_report(report_path, task_id)?;
+    let progress = ProgressHandle::disabled(task.id);
+    let replayed = run_typed(runner, task.clone(), recorded.worker_id, &progress)
+        .map_err(|e| e as Box<dyn Error>)?;
+    Ok(ReplayOutcome {
+        task,
+        recorded,
+        replayed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::common::DEFAULT_JOB_ID;
+    use crate::host::result_aggregator::ResultAggregator;
+    use crate::host::task_manager::{DistributionStrategy, TaskManager};
+
+    #[test]
+    fn replayed_task_keeps_objective_and_parameters() {
+        let mut tm = TaskManager::new(DistributionStrategy::Fifo);
+        tm.set_graph_objective(DEFAULT_JOB_ID, "g", Objective::Minimize);
+        tm.set_objective_directions(
+            DEFAULT_JOB_ID,
+            "g",
+            vec![Objective::Minimize, Objective::Maximize],
+        );
+        tm.add_new_graph_tasks("g", 1, "{}");
+        let worker_id = Uuid::new_v4();
+        let mut task = tm.get_next_task(worker_id).unwrap();
+        task.parameters
+            .insert("taxa".to_string(), serde_json::json!(0.5));
+
+        let mut aggregator = ResultAggregator::new();
+        aggregator
+            .add_task_result(
+                &task,
+                TaskResult {
+                    task_id: task.id,
+                    graph_id: task.graph_id.clone(),
+                    worker_id,
+                    fitness: 1.0,
+                    objectives: vec![1.0, 2.0],
+                    solution_data: Vec::new(),
+                    interations_run: 1,
+                    processing_time_ms: 1,
+                    seed: 0,
+                },
+            )
+            .unwrap();
+        let path = env::temp_dir().join(format!("kambo-replay-{}.json", Uuid::new_v4()));
+        let path = path.to_str().unwrap();
+        aggregator
+            .generate_and_save_report(&tm, DEFAULT_JOB_ID, path)
+            .unwrap();
+
+        let loaded = load_task_from_report(path, task.id);
+        let _ = fs::remove_file(path);
+        let (replayed, recorded) = loaded.unwrap();
+        assert_eq!(replayed.objective, Objective::Minimize);
+        assert_eq!(
+            replayed.objective_directions,
+            vec![Objective::Minimize, Objective::Maximize]
+        );
+        assert_eq!(replayed.parameters, task.parameters);
+        assert_eq!(replayed.seed, task.seed);
+        assert_eq!(recorded.seed, task.seed);
+    }
+}
@@ -0,0 +1,279 @@
+//! Host e workers no mesmo processo, ligados pelo transporte em memória.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use kambo_hive::common::{
+    ClusterSecret, DEFAULT_JOB_ID, GARunner, JsonPayload, ProgressHandle, Task, TaskResult,
+};
+use kambo_hive::host::dependencies::Dependency;
+use kambo_hive::host::result_aggregator::ResultAggregator;
+use kambo_hive::host::server::serve;
+use kambo_hive::host::task_manager::{
+    DistributionStrategy, GraphTaskOptions, TaskManager, TaskStatus,
+};
+use kambo_hive::transport;
+use kambo_hive::worker::client::{WorkerOptions, run_worker};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use tokio::time::{sleep, timeout};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize)]
+struct Config {
+    generations: u32,
+}
+
+impl JsonPayload for Config {}
+
+#[derive(Serialize, Deserialize)]
+struct Solution {
+    genes: Vec<u32>,
+}
+
+impl JsonPayload for Solution {}
+
+struct Runner;
+
+impl GARunner<Config, Solution> for Runner {
+    fn run(&self, task: Task<Config>, worker_id: Uuid) -> TaskResult<Solution> {
+        self.run_with_progress(task, worker_id, &ProgressHandle::disabled(Uuid::nil()))
+    }
+
+    fn run_with_progress(
+        &self,
+        task: Task<Config>,
+        worker_id: Uuid,
+        progress: &ProgressHandle,
+    ) -> TaskResult<Solution> {
+        for generation in 0..task.ag_config.generations {
+            progress.report(generation, f64::from(generation), 0.0);
+        }
+        TaskResult {
+            task_id: task.id,
+            graph_id: task.graph_id,
+            worker_id,
+            fitness: f64::from(task.run_number),
+            objectives: Vec::new(),
+            solution_data: Solution {
+                genes: vec![task.run_number],
+            },
+            interations_run: task.ag_config.generations,
+            processing_time_ms: 1,
+            seed: task.seed,
+        }
+    }
+}
+
+struct Cluster {
+    task_manager: Arc<Mutex<TaskManager>>,
+    result_aggregator: Arc<Mutex<ResultAggregator>>,
+    connector: transport::MemoryConnector,
+}
+
+impl Cluster {
+    fn start(task_manager: TaskManager, secret: Option<&ClusterSecret>) -> Self {
+        let task_manager = Arc::new(Mutex::new(task_manager));
+        let result_aggregator = Arc::new(Mutex::new(ResultAggregator::new()));
+        let (listener, connector) = transport::channel();
+
+        let server = serve(
+            listener,
+            Arc::clone(&task_manager),
+            Arc::clone(&result_aggregator),
+            secret.cloned(),
+        );
+        tokio::spawn(async move {
+            let _ = server.await;
+        });
+
+        Self {
+            task_manager,
+            result_aggregator,
+            connector,
+        }
+    }
+
+    fn spawn_workers(&self, count: usize, secret: Option<&ClusterSecret>) -> HashSet<Uuid> {
+        let options = WorkerOptions {
+            secret: secret.cloned(),
+            batch_size: 2,
+            task_wait_timeout: Duration::from_millis(50),
+            ..WorkerOptions::default()
+        };
+        (0..count)
+            .map(|_| {
+                let worker_id = Uuid::new_v4();
+                let connector = self.connector.clone();
+                let options = options.clone();
+                tokio::spawn(async move {
+                    let _ = run_worker(connector, worker_id, Arc::new(Runner), options).await;
+                });
+                worker_id
+            })
+            .collect()
+    }
+
+    async fn wait_until(&self, condition: impl Fn(&TaskManager) -> bool) {
+        timeout(Duration::from_secs(10), async {
+            while !condition(&*self.task_manager.lock().await) {
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("cluster não terminou a tempo");
+    }
+
+    async fn results(&self, graph_id: &str) -> Vec<TaskResult> {
+        self.result_aggregator
+            .lock()
+            .await
+            .get_all_results(DEFAULT_JOB_ID)
+            .and_then(|results| results.get(graph_id))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+fn config(generations: u32) -> String {
+    format!(r#"{{"generations": {generations}}}"#)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn workers_complete_every_task() {
+    let mut task_manager = TaskManager::new(DistributionStrategy::Fifo);
+    task_manager.add_new_graph_tasks("a", 10, &config(3));
+    task_manager
+        .add_typed_graph_tasks(
+            "b",
+            10,
+            &Config { generations: 3 },
+            &GraphTaskOptions::default(),
+        )
+        .unwrap();
+    let cluster = Cluster::start(task_manager, None);
+    let workers = cluster.spawn_workers(3, None);
+
+    cluster
+        .wait_until(|tm| tm.get_completed_tasks_count(DEFAULT_JOB_ID) == 20)
+        .await;
+
+    for graph_id in ["a", "b"] {
+        let results = cluster.results(graph_id).await;
+        assert_eq!(results.len(), 10);
+        assert!(
+            results
+                .iter()
+                .all(|result| workers.contains(&result.worker_id))
+        );
+
+        let mut runs: Vec<u32> = results
+            .into_iter()
+            .map(|result| result.into_typed::<Solution>().unwrap().solution_data.genes[0])
+            .collect();
+        runs.sort_unstable();
+        assert_eq!(runs, (0..10).collect::<Vec<_>>());
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn workers_with_wrong_secret_get_no_tasks() {
+    let mut task_manager = TaskManager::new(DistributionStrategy::Fifo);
+    task_manager.add_new_graph_tasks("g", 6, &config(1));
+    let secret = ClusterSecret::new("segredo");
+    let cluster = Cluster::start(task_manager, Some(&secret));
+    cluster.spawn_workers(2, Some(&ClusterSecret::new("errado")));
+    cluster.spawn_workers(1, None);
+    let workers = cluster.spawn_workers(2, Some(&secret));
+
+    cluster
+        .wait_until(|tm| tm.get_completed_tasks_count(DEFAULT_JOB_ID) == 6)
+        .await;
+    assert!(
+        cluster
+            .results("g")
+            .await
+            .iter()
+            .all(|result| workers.contains(&result.worker_id))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stage_runs_after_its_upstream_graph() {
+    let mut task_manager = TaskManager::new(DistributionStrategy::Fifo);
+    task_manager.add_new_graph_tasks("upstream", 4, &config(1));
+    task_manager.add_dependent_tasks(
+        DEFAULT_JOB_ID,
+        vec![Dependency::AllRunsOfGraph("upstream".to_string())],
+        vec![Task::new("downstream".to_string(), 0, config(1))],
+    );
+    let cluster = Cluster::start(task_manager, None);
+    cluster.spawn_workers(2, None);
+
+    cluster
+        .wait_until(|tm| {
+            tm.get_graph_progress(DEFAULT_JOB_ID, "downstream")
+                .is_finished()
+        })
+        .await;
+
+    assert!(
+        cluster
+            .task_manager
+            .lock()
+            .await
+            .get_graph_progress(DEFAULT_JOB_ID, "upstream")
+            .is_finished()
+    );
+    assert_eq!(cluster.results("downstream").await.len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn invalid_config_fails_after_max_attempts() {
+    let mut task_manager = TaskManager::new(DistributionStrategy::Fifo);
+    task_manager.set_max_task_attempts(2);
+    task_manager.add_new_graph_tasks("broken", 1, "não é json");
+    task_manager.add_new_graph_tasks("ok", 3, &config(1));
+    let cluster = Cluster::start(task_manager, None);
+    cluster.spawn_workers(2, None);
+
+    cluster
+        .wait_until(|tm| {
+            tm.count_tasks_with_status(DEFAULT_JOB_ID, TaskStatus::Failed) == 1
+                && tm.get_completed_tasks_count(DEFAULT_JOB_ID) == 3
+        })
+        .await;
+
+    let tm = cluster.task_manager.lock().await;
+    assert!(
+        tm.get_graph_progress(DEFAULT_JOB_ID, "broken")
+            .is_finished()
+    );
+    assert_eq!(tm.get_graph_progress(DEFAULT_JOB_ID, "broken").failed, 1);
+    assert!(cluster.results("broken").await.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn progress_curves_reach_the_host() {
+    let mut task_manager = TaskManager::new(DistributionStrategy::Fifo);
+    task_manager.add_new_graph_tasks("g", 2, &config(5));
+    let cluster = Cluster::start(task_manager, None);
+    cluster.spawn_workers(1, None);
+
+    cluster
+        .wait_until(|tm| tm.get_completed_tasks_count(DEFAULT_JOB_ID) == 2)
+        .await;
+
+    let results = cluster.results("g").await;
+    let aggregator = cluster.result_aggregator.lock().await;
+    for result in results {
+        let generations: Vec<u32> = aggregator
+            .get_convergence(DEFAULT_JOB_ID, result.task_id)
+            .expect("curva de convergência ausente")
+            .iter()
+            .map(|sample| sample.generation)
+            .collect();
+        assert_eq!(generations, vec![0, 1, 2, 3, 4]);
+    }
+}